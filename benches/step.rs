@@ -0,0 +1,60 @@
+//! Times `Cpu::step` and `Cpu::tick` on a tight loop. Run with
+//! `cargo bench --bench step`.
+
+use std::{hint::black_box, time::Instant};
+
+use bog::{Bus, Cpu, Pins};
+
+const PROGRAM_START: u16 = 0x0200;
+const STEP_CYCLES: u64 = 200_000_000;
+const TICK_CYCLES: u64 = 20_000_000;
+
+struct BenchBus {
+    memory: [u8; 0x10000],
+}
+
+impl Bus for BenchBus {
+    fn tick(&mut self, pins: &mut Pins) {
+        match pins.rw {
+            true => pins.data = self.memory[pins.address as usize],
+            false => self.memory[pins.address as usize] = pins.data,
+        }
+    }
+}
+
+fn new_cpu() -> Cpu<BenchBus> {
+    let mut memory = [0; 0x10000];
+    // LDA $10; STA $0300,X; INX; BNE -6; JMP $0200
+    let program = [0xa5, 0x10, 0x9d, 0x00, 0x03, 0xe8, 0xd0, 0xf8, 0x4c];
+    let start = PROGRAM_START as usize;
+    memory[start..start + program.len()].copy_from_slice(&program);
+    memory[start + program.len()..][..2].copy_from_slice(&[0x00, 0x02]);
+    memory[0xfffc] = PROGRAM_START as u8;
+    memory[0xfffd] = (PROGRAM_START >> 8) as u8;
+
+    let mut cpu = Cpu::new(BenchBus { memory });
+    cpu.step();
+    cpu
+}
+
+fn report(name: &str, cycles: u64, start: Instant) {
+    let seconds = start.elapsed().as_secs_f64();
+    let mhz = cycles as f64 / seconds / 1e6;
+    println!("{name}: {cycles} cycles in {seconds:.2}s ({mhz:.0} MHz)");
+}
+
+fn main() {
+    let mut cpu = new_cpu();
+    let start = Instant::now();
+    while cpu.cycles < STEP_CYCLES {
+        black_box(cpu.step());
+    }
+    report("step", STEP_CYCLES, start);
+
+    let mut cpu = new_cpu();
+    let start = Instant::now();
+    while cpu.cycles < TICK_CYCLES {
+        black_box(cpu.tick());
+    }
+    report("tick", TICK_CYCLES, start);
+}
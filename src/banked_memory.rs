@@ -0,0 +1,197 @@
+use core::ops::RangeInclusive;
+
+use crate::{Bus, Pins};
+
+/// Where a window of a [`BankedMemory`] is mapped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Window {
+    /// The first address of the window.
+    pub start: u16,
+    /// The last address of the window.
+    pub end: u16,
+    /// The offset into the backing memory that reads come from, or `None` if
+    /// reads leave the data bus alone.
+    pub read: Option<usize>,
+    /// The offset into the backing memory that writes go to, or `None` if
+    /// writes are ignored.
+    pub write: Option<usize>,
+}
+
+impl Window {
+    /// Returns the number of bytes in the window.
+    pub fn size(&self) -> usize {
+        (self.end - self.start) as usize + 1
+    }
+
+    fn contains(&self, address: u16) -> bool {
+        (self.start..=self.end).contains(&address)
+    }
+}
+
+/// A [`Bus`] that maps `N` windows of the address space to offsets in a
+/// larger backing memory, which can be switched at run time.
+///
+/// Reads and writes are mapped separately, so a window can, e.g., read ROM
+/// while writing to the RAM under it. Windows that come later take priority
+/// over the ones they overlap, except where they're unmapped, and offsets
+/// past the end of the backing memory wrap around. Mappers wrap a
+/// `BankedMemory` in a bus of their own that switches banks when their
+/// registers are written.
+pub struct BankedMemory<'a, const N: usize> {
+    memory: &'a mut [u8],
+    windows: [Window; N],
+}
+
+impl<'a, const N: usize> BankedMemory<'a, N> {
+    /// Constructs a new `BankedMemory` with the given windows, which start
+    /// out unmapped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `memory` is empty or a window's range is reversed.
+    pub fn new(
+        memory: &'a mut [u8],
+        windows: [RangeInclusive<u16>; N],
+    ) -> BankedMemory<'a, N> {
+        assert!(!memory.is_empty(), "backing memory is empty");
+        for range in &windows {
+            assert!(range.start() <= range.end(), "window range is reversed");
+        }
+
+        BankedMemory {
+            memory,
+            windows: windows.map(|range| Window {
+                start: *range.start(),
+                end: *range.end(),
+                read: None,
+                write: None,
+            }),
+        }
+    }
+
+    /// Maps reads and writes in window `index` to `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn map(&mut self, index: usize, offset: usize) {
+        self.map_read(index, Some(offset));
+        self.map_write(index, Some(offset));
+    }
+
+    /// Maps reads in window `index` to `offset`, or unmaps them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn map_read(&mut self, index: usize, offset: Option<usize>) {
+        self.windows[index].read = offset.map(|offset| self.wrap(offset));
+    }
+
+    /// Maps writes in window `index` to `offset`, or unmaps them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn map_write(&mut self, index: usize, offset: Option<usize>) {
+        self.windows[index].write = offset.map(|offset| self.wrap(offset));
+    }
+
+    /// Maps reads and writes in window `index` to the bank `bank`, where
+    /// banks are the size of the window.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range or the offset of the bank overflows
+    /// a `usize`.
+    pub fn select(&mut self, index: usize, bank: usize) {
+        let offset = bank
+            .checked_mul(self.windows[index].size())
+            .expect("bank offset overflows");
+        self.map(index, offset);
+    }
+
+    /// Returns the current mapping of window `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn window(&self, index: usize) -> Window {
+        self.windows[index]
+    }
+
+    /// Returns the current mappings of all the windows.
+    pub fn windows(&self) -> &[Window; N] {
+        &self.windows
+    }
+
+    /// Returns the backing memory.
+    pub fn memory(&self) -> &[u8] {
+        self.memory
+    }
+
+    /// Returns the backing memory mutably.
+    pub fn memory_mut(&mut self) -> &mut [u8] {
+        self.memory
+    }
+
+    /// Returns the offset into the backing memory that a read from `address`
+    /// comes from.
+    pub fn read_offset(&self, address: u16) -> Option<usize> {
+        self.offset(address, |window| window.read)
+    }
+
+    /// Returns the offset into the backing memory that a write to `address`
+    /// goes to.
+    pub fn write_offset(&self, address: u16) -> Option<usize> {
+        self.offset(address, |window| window.write)
+    }
+
+    // Offsets are wrapped into the backing memory when they're mapped, so
+    // adding an address to one can't overflow.
+    fn wrap(&self, offset: usize) -> usize {
+        offset % self.memory.len()
+    }
+
+    fn offset<F>(&self, address: u16, f: F) -> Option<usize>
+    where
+        F: Fn(&Window) -> Option<usize>,
+    {
+        // The latest window that's mapped at the address wins.
+        self.windows.iter().rev().find_map(|window| {
+            let offset = f(window).filter(|_| window.contains(address))?;
+            Some(self.wrap(offset + (address - window.start) as usize))
+        })
+    }
+}
+
+impl<const N: usize> Bus for BankedMemory<'_, N> {
+    fn tick(&mut self, pins: &mut Pins) {
+        match pins.rw {
+            true => {
+                if let Some(offset) = self.read_offset(pins.address) {
+                    pins.data = self.memory[offset];
+                }
+            }
+            false => {
+                if let Some(offset) = self.write_offset(pins.address) {
+                    self.memory[offset] = pins.data;
+                }
+            }
+        }
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        self.read_offset(address).map(|offset| self.memory[offset])
+    }
+
+    fn poke(&mut self, address: u16, data: u8) -> bool {
+        match self.write_offset(address) {
+            Some(offset) => {
+                self.memory[offset] = data;
+                true
+            }
+            None => false,
+        }
+    }
+}
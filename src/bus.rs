@@ -1,5 +1,24 @@
 use crate::Pins;
 
+/// The system bus that a `Cpu` reads from and writes to.
 pub trait Bus {
+    /// Runs one bus cycle. For reads, the bus puts the data on `pins.data`.
+    ///
+    /// `pins.data` still holds the last value on the data bus, so a read that
+    /// nothing answers should leave it alone, like open bus on real hardware.
+    /// [`OpenBus`](crate::OpenBus) can track the value instead, e.g., to
+    /// emulate it decaying.
     fn tick(&mut self, pins: &mut Pins);
+
+    /// Reads `address` without side effects, e.g., for a disassembler or a
+    /// memory viewer. Returns `None` if the bus can't, which is the default.
+    fn peek(&self, _address: u16) -> Option<u8> {
+        None
+    }
+
+    /// Writes `data` to `address` without side effects, e.g., for a debugger.
+    /// Returns whether the write happened. The default ignores it.
+    fn poke(&mut self, _address: u16, _data: u8) -> bool {
+        false
+    }
 }
@@ -1,6 +1,11 @@
+use core::marker::PhantomData;
+
 use bitflags::bitflags;
 
-use crate::{Bus, Pins};
+use crate::{
+    port::IoPort, Bus, CycleKind, Inspector, InstructionSet, Nmos,
+    NoInspector, Pins, ShModel, Unstable, Variant,
+};
 
 const NMI_VECTOR: u16 = 0xfffa;
 const RESET_VECTOR: u16 = 0xfffc;
@@ -18,6 +23,8 @@ const INDIRECT_INDEXED: u8 = 8;
 const ZERO_PAGE: u8 = 9;
 const ZERO_PAGE_X: u8 = 10;
 const ZERO_PAGE_Y: u8 = 11;
+const ZERO_PAGE_INDIRECT: u8 = 12;
+const ABSOLUTE_X_INDIRECT: u8 = 13;
 
 const ASL: u8 = 0;
 const DCP: u8 = 1;
@@ -31,6 +38,8 @@ const ROR: u8 = 8;
 const RRA: u8 = 9;
 const SLO: u8 = 10;
 const SRE: u8 = 11;
+const TRB: u8 = 12;
+const TSB: u8 = 13;
 
 const BRK: u8 = 0;
 const IRQ: u8 = 1;
@@ -39,6 +48,11 @@ const RST: u8 = 3;
 
 const STACK_BASE: u16 = 0x0100;
 
+// The most bus accesses an instruction, or interrupt sequence, makes, which is
+// the eight of the CMOS NOP at 0x5C. RDY can stall an access for any number of
+// cycles, but it still takes a single entry.
+const LOG_CAPACITY: usize = 8;
+
 bitflags! {
     /// The status register bitflags.
     #[derive(Clone, Copy)]
@@ -60,8 +74,164 @@ impl Default for Status {
     }
 }
 
+/// Whether a `Cpu` is executing instructions or halted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunState {
+    Running,
+    /// Halted by WAI until an interrupt arrives.
+    Waiting,
+    /// Halted by STP until reset.
+    Stopped,
+    /// Locked up by a JAM opcode until reset.
+    Jammed,
+}
+
+/// An interrupt sequence that a `Cpu` ran.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interrupt {
+    Irq,
+    Nmi,
+    Reset,
+}
+
+/// What happened during a call to [`Cpu::step`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StepResult {
+    /// The opcode of the instruction that ran, if any.
+    pub opcode: Option<u8>,
+    /// The interrupt sequence that ran instead of an instruction, if any.
+    pub interrupt: Option<Interrupt>,
+    /// The number of cycles it took.
+    pub cycles: u64,
+    /// Whether the CPU is executing instructions or halted afterwards.
+    pub state: RunState,
+    /// Whether the instruction jumped or branched to itself, which test
+    /// programs use to signal that they're done.
+    pub trapped: bool,
+}
+
+// The state an instruction can change, other than the pins and the bus.
+#[derive(Clone, Copy)]
+struct Snapshot {
+    a: u8,
+    x: u8,
+    y: u8,
+    pc: u16,
+    s: u8,
+    p: Status,
+    cycles: u64,
+    prev_irq: bool,
+    irq: bool,
+    prev_nmi: bool,
+    prev_so: bool,
+    prev_need_nmi: bool,
+    need_nmi: bool,
+    rst: bool,
+    state: RunState,
+    stalled: bool,
+    port: IoPort,
+}
+
+// What happens to a cycle when `Cpu::tick` runs an instruction again.
+enum Cycle {
+    Replayed,
+    New,
+    Skipped,
+}
+
+// Instructions always run to completion, so `Cpu::tick` can't stop partway
+// through one. Instead, it runs the instruction from a snapshot of its start,
+// replays the accesses the bus already saw from a log, ticks the bus for one
+// new cycle, and skips the rest. None of this is done while the log is empty,
+// so `Cpu::step` doesn't pay for it.
+struct Resume {
+    // Whether reads and writes go through the log.
+    active: bool,
+    snapshot: Snapshot,
+    // The state and pins right after the bus saw the last cycle of each
+    // access, and whether the access was done then, i.e., not stalled. An
+    // access is replayed by restoring its entry, so nothing is lost however
+    // long RDY stalls it.
+    log: [(Snapshot, Pins, bool); LOG_CAPACITY],
+    len: usize,
+    // The entry of the current access, and of the next one.
+    access: usize,
+    next: usize,
+    // Whether the bus only gets one new cycle, and whether it saw it.
+    limited: bool,
+    ticked: bool,
+    // The state between the new cycle and the next one, if the instruction
+    // got that far.
+    paused: Option<Snapshot>,
+    // Whether the last access was new to the bus, i.e., not replayed or
+    // skipped.
+    real: bool,
+}
+
+impl Resume {
+    fn new() -> Resume {
+        let snapshot = Snapshot {
+            a: 0,
+            x: 0,
+            y: 0,
+            pc: 0,
+            s: 0,
+            p: Status::default(),
+            cycles: 0,
+            prev_irq: false,
+            irq: false,
+            prev_nmi: false,
+            prev_so: false,
+            prev_need_nmi: false,
+            need_nmi: false,
+            rst: false,
+            state: RunState::Running,
+            stalled: false,
+            port: IoPort::new(),
+        };
+
+        Resume {
+            active: false,
+            snapshot,
+            log: [(snapshot, Pins::default(), true); LOG_CAPACITY],
+            len: 0,
+            access: 0,
+            next: 0,
+            limited: false,
+            ticked: false,
+            paused: None,
+            real: true,
+        }
+    }
+
+    fn start(&mut self, limited: bool) {
+        self.active = true;
+        self.next = 0;
+        self.limited = limited;
+        self.ticked = false;
+        self.paused = None;
+    }
+
+    fn stop(&mut self) {
+        self.active = false;
+        self.limited = false;
+        self.real = true;
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    fn last(&self) -> Pins {
+        self.log[self.len - 1].1
+    }
+}
+
+// An instruction, or interrupt sequence, in an opcode LUT.
+type Instruction<B, V, T> = fn(&mut Cpu<B, V, T>);
+
 /// A MOS 6502 CPU.
-pub struct Cpu<B> {
+pub struct Cpu<B, V = Nmos, T = NoInspector> {
     pub a: u8,
     pub x: u8,
     pub y: u8,
@@ -70,22 +240,41 @@ pub struct Cpu<B> {
     pub p: Status,
     pub pins: Pins,
     pub cycles: u64,
+    /// How the unstable illegal opcodes behave.
+    pub unstable: Unstable,
 
     prev_irq: bool,
     irq: bool,
     prev_nmi: bool,
+    prev_so: bool,
     prev_need_nmi: bool,
     need_nmi: bool,
     rst: bool,
 
+    state: RunState,
+    // Whether RDY stalled the last read.
+    stalled: bool,
+    // The state when a reset aborted the current instruction, which keeps it
+    // from changing the registers.
+    aborted: Option<Snapshot>,
+
+    port: IoPort,
+
+    resume: Resume,
+
     pub bus: B,
+    pub inspector: T,
+
+    variant: PhantomData<V>,
 }
 
-impl<B> Cpu<B>
+impl<B, V, T> Cpu<B, V, T>
 where
     B: Bus,
+    V: Variant,
+    T: Inspector,
 {
-    const OPCODE_LUT: [fn(&mut Cpu<B>); 256] = [
+    const NMOS_OPCODE_LUT: [Instruction<B, V, T>; 256] = [
         Cpu::brk::<BRK>,
         Cpu::ora::<INDEXED_INDIRECT>,
         Cpu::jam,
@@ -233,7 +422,7 @@ where
         Cpu::bcc,
         Cpu::sta::<INDIRECT_INDEXED>,
         Cpu::jam,
-        Cpu::sha::<ABSOLUTE_Y>,
+        Cpu::sha::<INDIRECT_INDEXED>,
         Cpu::sty::<ZERO_PAGE_X>,
         Cpu::sta::<ZERO_PAGE_X>,
         Cpu::stx::<ZERO_PAGE_Y>,
@@ -245,7 +434,7 @@ where
         Cpu::shy::<ABSOLUTE_X>,
         Cpu::sta::<ABSOLUTE_X>,
         Cpu::shx::<ABSOLUTE_Y>,
-        Cpu::sha::<INDIRECT_INDEXED>,
+        Cpu::sha::<ABSOLUTE_Y>,
         Cpu::ldy::<IMMEDIATE>,
         Cpu::lda::<INDEXED_INDIRECT>,
         Cpu::ldx::<IMMEDIATE>,
@@ -344,61 +533,829 @@ where
         Cpu::isb::<ABSOLUTE_X>,
     ];
 
-    /// Constructs a new `Cpu` in a power-up state.
-    pub fn new(bus: B) -> Cpu<B> {
+    const CMOS_OPCODE_LUT: [Instruction<B, V, T>; 256] = [
+        Cpu::brk::<BRK>,
+        Cpu::ora::<INDEXED_INDIRECT>,
+        Cpu::nop::<IMMEDIATE>,
+        Cpu::nop1,
+        Cpu::tsb::<ZERO_PAGE>,
+        Cpu::ora::<ZERO_PAGE>,
+        Cpu::asl::<ZERO_PAGE>,
+        Cpu::nop1,
+        Cpu::php,
+        Cpu::ora::<IMMEDIATE>,
+        Cpu::asl::<ACCUMULATOR>,
+        Cpu::nop1,
+        Cpu::tsb::<ABSOLUTE>,
+        Cpu::ora::<ABSOLUTE>,
+        Cpu::asl::<ABSOLUTE>,
+        Cpu::nop1,
+        Cpu::bpl,
+        Cpu::ora::<INDIRECT_INDEXED>,
+        Cpu::ora::<ZERO_PAGE_INDIRECT>,
+        Cpu::nop1,
+        Cpu::trb::<ZERO_PAGE>,
+        Cpu::ora::<ZERO_PAGE_X>,
+        Cpu::asl::<ZERO_PAGE_X>,
+        Cpu::nop1,
+        Cpu::clc,
+        Cpu::ora::<ABSOLUTE_Y>,
+        Cpu::inc::<ACCUMULATOR>,
+        Cpu::nop1,
+        Cpu::trb::<ABSOLUTE>,
+        Cpu::ora::<ABSOLUTE_X>,
+        Cpu::asl::<ABSOLUTE_X>,
+        Cpu::nop1,
+        Cpu::jsr,
+        Cpu::and::<INDEXED_INDIRECT>,
+        Cpu::nop::<IMMEDIATE>,
+        Cpu::nop1,
+        Cpu::bit::<ZERO_PAGE>,
+        Cpu::and::<ZERO_PAGE>,
+        Cpu::rol::<ZERO_PAGE>,
+        Cpu::nop1,
+        Cpu::plp,
+        Cpu::and::<IMMEDIATE>,
+        Cpu::rol::<ACCUMULATOR>,
+        Cpu::nop1,
+        Cpu::bit::<ABSOLUTE>,
+        Cpu::and::<ABSOLUTE>,
+        Cpu::rol::<ABSOLUTE>,
+        Cpu::nop1,
+        Cpu::bmi,
+        Cpu::and::<INDIRECT_INDEXED>,
+        Cpu::and::<ZERO_PAGE_INDIRECT>,
+        Cpu::nop1,
+        Cpu::bit::<ZERO_PAGE_X>,
+        Cpu::and::<ZERO_PAGE_X>,
+        Cpu::rol::<ZERO_PAGE_X>,
+        Cpu::nop1,
+        Cpu::sec,
+        Cpu::and::<ABSOLUTE_Y>,
+        Cpu::dec::<ACCUMULATOR>,
+        Cpu::nop1,
+        Cpu::bit::<ABSOLUTE_X>,
+        Cpu::and::<ABSOLUTE_X>,
+        Cpu::rol::<ABSOLUTE_X>,
+        Cpu::nop1,
+        Cpu::rti,
+        Cpu::eor::<INDEXED_INDIRECT>,
+        Cpu::nop::<IMMEDIATE>,
+        Cpu::nop1,
+        Cpu::nop::<ZERO_PAGE>,
+        Cpu::eor::<ZERO_PAGE>,
+        Cpu::lsr::<ZERO_PAGE>,
+        Cpu::nop1,
+        Cpu::pha,
+        Cpu::eor::<IMMEDIATE>,
+        Cpu::lsr::<ACCUMULATOR>,
+        Cpu::nop1,
+        Cpu::jmp::<ABSOLUTE>,
+        Cpu::eor::<ABSOLUTE>,
+        Cpu::lsr::<ABSOLUTE>,
+        Cpu::nop1,
+        Cpu::bvc,
+        Cpu::eor::<INDIRECT_INDEXED>,
+        Cpu::eor::<ZERO_PAGE_INDIRECT>,
+        Cpu::nop1,
+        Cpu::nop::<ZERO_PAGE_X>,
+        Cpu::eor::<ZERO_PAGE_X>,
+        Cpu::lsr::<ZERO_PAGE_X>,
+        Cpu::nop1,
+        Cpu::cli,
+        Cpu::eor::<ABSOLUTE_Y>,
+        Cpu::phy,
+        Cpu::nop1,
+        Cpu::nop8,
+        Cpu::eor::<ABSOLUTE_X>,
+        Cpu::lsr::<ABSOLUTE_X>,
+        Cpu::nop1,
+        Cpu::rts,
+        Cpu::adc::<INDEXED_INDIRECT>,
+        Cpu::nop::<IMMEDIATE>,
+        Cpu::nop1,
+        Cpu::stz::<ZERO_PAGE>,
+        Cpu::adc::<ZERO_PAGE>,
+        Cpu::ror::<ZERO_PAGE>,
+        Cpu::nop1,
+        Cpu::pla,
+        Cpu::adc::<IMMEDIATE>,
+        Cpu::ror::<ACCUMULATOR>,
+        Cpu::nop1,
+        Cpu::jmp::<INDIRECT>,
+        Cpu::adc::<ABSOLUTE>,
+        Cpu::ror::<ABSOLUTE>,
+        Cpu::nop1,
+        Cpu::bvs,
+        Cpu::adc::<INDIRECT_INDEXED>,
+        Cpu::adc::<ZERO_PAGE_INDIRECT>,
+        Cpu::nop1,
+        Cpu::stz::<ZERO_PAGE_X>,
+        Cpu::adc::<ZERO_PAGE_X>,
+        Cpu::ror::<ZERO_PAGE_X>,
+        Cpu::nop1,
+        Cpu::sei,
+        Cpu::adc::<ABSOLUTE_Y>,
+        Cpu::ply,
+        Cpu::nop1,
+        Cpu::jmp::<ABSOLUTE_X_INDIRECT>,
+        Cpu::adc::<ABSOLUTE_X>,
+        Cpu::ror::<ABSOLUTE_X>,
+        Cpu::nop1,
+        Cpu::bra,
+        Cpu::sta::<INDEXED_INDIRECT>,
+        Cpu::nop::<IMMEDIATE>,
+        Cpu::nop1,
+        Cpu::sty::<ZERO_PAGE>,
+        Cpu::sta::<ZERO_PAGE>,
+        Cpu::stx::<ZERO_PAGE>,
+        Cpu::nop1,
+        Cpu::dey,
+        Cpu::bit::<IMMEDIATE>,
+        Cpu::txa,
+        Cpu::nop1,
+        Cpu::sty::<ABSOLUTE>,
+        Cpu::sta::<ABSOLUTE>,
+        Cpu::stx::<ABSOLUTE>,
+        Cpu::nop1,
+        Cpu::bcc,
+        Cpu::sta::<INDIRECT_INDEXED>,
+        Cpu::sta::<ZERO_PAGE_INDIRECT>,
+        Cpu::nop1,
+        Cpu::sty::<ZERO_PAGE_X>,
+        Cpu::sta::<ZERO_PAGE_X>,
+        Cpu::stx::<ZERO_PAGE_Y>,
+        Cpu::nop1,
+        Cpu::tya,
+        Cpu::sta::<ABSOLUTE_Y>,
+        Cpu::txs,
+        Cpu::nop1,
+        Cpu::stz::<ABSOLUTE>,
+        Cpu::sta::<ABSOLUTE_X>,
+        Cpu::stz::<ABSOLUTE_X>,
+        Cpu::nop1,
+        Cpu::ldy::<IMMEDIATE>,
+        Cpu::lda::<INDEXED_INDIRECT>,
+        Cpu::ldx::<IMMEDIATE>,
+        Cpu::nop1,
+        Cpu::ldy::<ZERO_PAGE>,
+        Cpu::lda::<ZERO_PAGE>,
+        Cpu::ldx::<ZERO_PAGE>,
+        Cpu::nop1,
+        Cpu::tay,
+        Cpu::lda::<IMMEDIATE>,
+        Cpu::tax,
+        Cpu::nop1,
+        Cpu::ldy::<ABSOLUTE>,
+        Cpu::lda::<ABSOLUTE>,
+        Cpu::ldx::<ABSOLUTE>,
+        Cpu::nop1,
+        Cpu::bcs,
+        Cpu::lda::<INDIRECT_INDEXED>,
+        Cpu::lda::<ZERO_PAGE_INDIRECT>,
+        Cpu::nop1,
+        Cpu::ldy::<ZERO_PAGE_X>,
+        Cpu::lda::<ZERO_PAGE_X>,
+        Cpu::ldx::<ZERO_PAGE_Y>,
+        Cpu::nop1,
+        Cpu::clv,
+        Cpu::lda::<ABSOLUTE_Y>,
+        Cpu::tsx,
+        Cpu::nop1,
+        Cpu::ldy::<ABSOLUTE_X>,
+        Cpu::lda::<ABSOLUTE_X>,
+        Cpu::ldx::<ABSOLUTE_Y>,
+        Cpu::nop1,
+        Cpu::cpy::<IMMEDIATE>,
+        Cpu::cmp::<INDEXED_INDIRECT>,
+        Cpu::nop::<IMMEDIATE>,
+        Cpu::nop1,
+        Cpu::cpy::<ZERO_PAGE>,
+        Cpu::cmp::<ZERO_PAGE>,
+        Cpu::dec::<ZERO_PAGE>,
+        Cpu::nop1,
+        Cpu::iny,
+        Cpu::cmp::<IMMEDIATE>,
+        Cpu::dex,
+        Cpu::nop1,
+        Cpu::cpy::<ABSOLUTE>,
+        Cpu::cmp::<ABSOLUTE>,
+        Cpu::dec::<ABSOLUTE>,
+        Cpu::nop1,
+        Cpu::bne,
+        Cpu::cmp::<INDIRECT_INDEXED>,
+        Cpu::cmp::<ZERO_PAGE_INDIRECT>,
+        Cpu::nop1,
+        Cpu::nop::<ZERO_PAGE_X>,
+        Cpu::cmp::<ZERO_PAGE_X>,
+        Cpu::dec::<ZERO_PAGE_X>,
+        Cpu::nop1,
+        Cpu::cld,
+        Cpu::cmp::<ABSOLUTE_Y>,
+        Cpu::phx,
+        Cpu::nop1,
+        Cpu::nop::<ABSOLUTE>,
+        Cpu::cmp::<ABSOLUTE_X>,
+        Cpu::dec::<ABSOLUTE_X>,
+        Cpu::nop1,
+        Cpu::cpx::<IMMEDIATE>,
+        Cpu::sbc::<INDEXED_INDIRECT>,
+        Cpu::nop::<IMMEDIATE>,
+        Cpu::nop1,
+        Cpu::cpx::<ZERO_PAGE>,
+        Cpu::sbc::<ZERO_PAGE>,
+        Cpu::inc::<ZERO_PAGE>,
+        Cpu::nop1,
+        Cpu::inx,
+        Cpu::sbc::<IMMEDIATE>,
+        Cpu::nop::<IMPLIED>,
+        Cpu::nop1,
+        Cpu::cpx::<ABSOLUTE>,
+        Cpu::sbc::<ABSOLUTE>,
+        Cpu::inc::<ABSOLUTE>,
+        Cpu::nop1,
+        Cpu::beq,
+        Cpu::sbc::<INDIRECT_INDEXED>,
+        Cpu::sbc::<ZERO_PAGE_INDIRECT>,
+        Cpu::nop1,
+        Cpu::nop::<ZERO_PAGE_X>,
+        Cpu::sbc::<ZERO_PAGE_X>,
+        Cpu::inc::<ZERO_PAGE_X>,
+        Cpu::nop1,
+        Cpu::sed,
+        Cpu::sbc::<ABSOLUTE_Y>,
+        Cpu::plx,
+        Cpu::nop1,
+        Cpu::nop::<ABSOLUTE>,
+        Cpu::sbc::<ABSOLUTE_X>,
+        Cpu::inc::<ABSOLUTE_X>,
+        Cpu::nop1,
+    ];
+
+    const ROCKWELL_OPCODE_LUT: [Instruction<B, V, T>; 256] = {
+        let mut lut = Cpu::CMOS_OPCODE_LUT;
+        lut[0x07] = Cpu::rmb::<0>;
+        lut[0x17] = Cpu::rmb::<1>;
+        lut[0x27] = Cpu::rmb::<2>;
+        lut[0x37] = Cpu::rmb::<3>;
+        lut[0x47] = Cpu::rmb::<4>;
+        lut[0x57] = Cpu::rmb::<5>;
+        lut[0x67] = Cpu::rmb::<6>;
+        lut[0x77] = Cpu::rmb::<7>;
+        lut[0x87] = Cpu::smb::<0>;
+        lut[0x97] = Cpu::smb::<1>;
+        lut[0xa7] = Cpu::smb::<2>;
+        lut[0xb7] = Cpu::smb::<3>;
+        lut[0xc7] = Cpu::smb::<4>;
+        lut[0xd7] = Cpu::smb::<5>;
+        lut[0xe7] = Cpu::smb::<6>;
+        lut[0xf7] = Cpu::smb::<7>;
+        lut[0x0f] = Cpu::bbr::<0>;
+        lut[0x1f] = Cpu::bbr::<1>;
+        lut[0x2f] = Cpu::bbr::<2>;
+        lut[0x3f] = Cpu::bbr::<3>;
+        lut[0x4f] = Cpu::bbr::<4>;
+        lut[0x5f] = Cpu::bbr::<5>;
+        lut[0x6f] = Cpu::bbr::<6>;
+        lut[0x7f] = Cpu::bbr::<7>;
+        lut[0x8f] = Cpu::bbs::<0>;
+        lut[0x9f] = Cpu::bbs::<1>;
+        lut[0xaf] = Cpu::bbs::<2>;
+        lut[0xbf] = Cpu::bbs::<3>;
+        lut[0xcf] = Cpu::bbs::<4>;
+        lut[0xdf] = Cpu::bbs::<5>;
+        lut[0xef] = Cpu::bbs::<6>;
+        lut[0xff] = Cpu::bbs::<7>;
+        lut
+    };
+
+    const WDC_OPCODE_LUT: [Instruction<B, V, T>; 256] = {
+        let mut lut = Cpu::ROCKWELL_OPCODE_LUT;
+        lut[0xcb] = Cpu::wai;
+        lut[0xdb] = Cpu::stp;
+        lut
+    };
+
+    // The CMOS chips fix most of the NMOS bugs, and in doing so change the
+    // bus activity of several addressing modes.
+    const CMOS: bool = !matches!(V::INSTRUCTION_SET, InstructionSet::Nmos);
+
+    /// Constructs a new `Cpu` of the given variant in a power-up state, with
+    /// an inspector to observe it.
+    pub fn with_inspector(bus: B, _variant: V, inspector: T) -> Cpu<B, V, T> {
         Cpu {
             a: 0,
             x: 0,
             y: 0,
             pc: 0,
-            s: 0xfd,
+            // The reset sequence decrements it to 0xfd.
+            s: 0x00,
             p: Status::default(),
             pins: Pins::default(),
             cycles: 0,
+            unstable: V::UNSTABLE,
             prev_irq: false,
             irq: false,
             prev_nmi: false,
+            prev_so: false,
             prev_need_nmi: false,
             need_nmi: false,
             rst: true,
+            state: RunState::Running,
+            stalled: false,
+            aborted: None,
+            port: IoPort::new(),
+            resume: Resume::new(),
             bus,
+            inspector,
+            variant: PhantomData,
+        }
+    }
+
+    /// Executes the next instruction, or a single cycle if the CPU is
+    /// halted, and returns the resulting state.
+    ///
+    /// If [`Cpu::tick`] stopped partway through an instruction, this finishes
+    /// it, unless the registers were changed since.
+    pub fn step(&mut self) -> StepResult {
+        let resumed = self.resume.len > 0 && self.rewind();
+        let (pc, cycles) = (self.pc, self.cycles);
+
+        let (opcode, interrupt) = if resumed {
+            self.resume.start(false);
+            let result = self.execute();
+            self.resume.stop();
+            self.resume.clear();
+            result
+        } else {
+            self.execute()
+        };
+
+        StepResult {
+            opcode,
+            interrupt,
+            cycles: self.cycles - cycles,
+            state: self.state,
+            // An instruction that a reset aborted didn't finish.
+            trapped: opcode.is_some() && !self.rst && self.pc == pc,
+        }
+    }
+
+    /// Executes instructions until at least `cycles` cycles have passed and
+    /// returns how many cycles past that it ran.
+    pub fn run_for_cycles(&mut self, cycles: u64) -> u64 {
+        let end = self.cycles + cycles;
+        while self.cycles < end {
+            self.step();
+        }
+
+        self.cycles - end
+    }
+
+    /// Executes bus cycles with [`Cpu::tick`] until [`Cpu::cycles`] reaches
+    /// at least `cycle`, which can be partway through an instruction, and
+    /// returns how many cycles past that it ran. Only wait states make it run
+    /// past.
+    ///
+    /// This is for running until the next event of a
+    /// [`Scheduler`](crate::Scheduler): pins changed afterwards, like an IRQ
+    /// being asserted, are seen on the cycle the event was due.
+    pub fn run_until_cycle(&mut self, cycle: u64) -> u64 {
+        while self.cycles < cycle {
+            self.tick();
+        }
+
+        self.cycles - cycle
+    }
+
+    /// Executes instructions until `f` returns true and returns the result of
+    /// the last one.
+    pub fn run_until<F>(&mut self, mut f: F) -> StepResult
+    where
+        F: FnMut(&Cpu<B, V, T>) -> bool,
+    {
+        loop {
+            let result = self.step();
+            if f(self) {
+                return result;
+            }
+        }
+    }
+
+    /// Executes instructions until one jumps or branches to itself, or the
+    /// CPU halts, and returns the result of the last one.
+    ///
+    /// A CPU that WAI halted only wakes up for an interrupt, so this returns
+    /// rather than waiting for one.
+    pub fn run_until_trap(&mut self) -> StepResult {
+        loop {
+            let result = self.step();
+            if result.trapped || result.state != RunState::Running {
+                return result;
+            }
+        }
+    }
+
+    /// Executes a single bus cycle and returns the pins for it.
+    ///
+    /// Instructions still run as a whole, so each call runs the current one
+    /// again from its start and replays the bus accesses that already
+    /// happened from a log. An instruction makes at most 8 accesses, but
+    /// ticking through one costs quadratically more than stepping it.
+    ///
+    /// Between calls, the registers and `cycles` are as they were after the
+    /// last cycle. Changes to `pins` between calls, like asserting IRQ, are
+    /// seen as if the bus made them during the previous cycle, and changes to
+    /// `cycles` are kept.
+    ///
+    /// Changing the registers partway through an instruction drops the rest
+    /// of it, since running it again would undo the change. The next call
+    /// starts a new instruction from the changed registers instead.
+    pub fn tick(&mut self) -> Pins {
+        if self.resume.len == 0 || !self.rewind() {
+            self.resume.snapshot = self.save();
+        }
+
+        self.resume.start(true);
+        self.execute();
+        self.resume.stop();
+
+        // A reset can end an instruction before the new cycle, in which case
+        // the cycle belongs to the reset sequence.
+        if !self.resume.ticked {
+            self.resume.clear();
+            return self.tick();
+        }
+
+        let pins = self.resume.last();
+        if let Some(paused) = self.resume.paused {
+            self.restore(&paused);
+            self.pins = pins;
+        } else {
+            self.resume.clear();
+        }
+
+        pins
+    }
+
+    fn save(&self) -> Snapshot {
+        Snapshot {
+            a: self.a,
+            x: self.x,
+            y: self.y,
+            pc: self.pc,
+            s: self.s,
+            p: self.p,
+            cycles: self.cycles,
+            prev_irq: self.prev_irq,
+            irq: self.irq,
+            prev_nmi: self.prev_nmi,
+            prev_so: self.prev_so,
+            prev_need_nmi: self.prev_need_nmi,
+            need_nmi: self.need_nmi,
+            rst: self.rst,
+            state: self.state,
+            stalled: self.stalled,
+            port: self.port,
         }
     }
 
-    /// Executes the next instruction.
-    pub fn step(&mut self) {
-        if self.rst || self.prev_need_nmi || self.prev_irq {
-            let brk_fn = if self.rst {
-                // TODO: Reset CPU struct fields?
-                self.rst = false;
-                Cpu::brk::<RST>
-            } else if self.prev_need_nmi {
-                self.need_nmi = false;
-                Cpu::brk::<NMI>
-            } else {
-                Cpu::brk::<IRQ>
-            };
+    fn restore(&mut self, snapshot: &Snapshot) {
+        self.a = snapshot.a;
+        self.x = snapshot.x;
+        self.y = snapshot.y;
+        self.pc = snapshot.pc;
+        self.s = snapshot.s;
+        self.p = snapshot.p;
+        self.cycles = snapshot.cycles;
+        self.prev_irq = snapshot.prev_irq;
+        self.irq = snapshot.irq;
+        self.prev_nmi = snapshot.prev_nmi;
+        self.prev_so = snapshot.prev_so;
+        self.prev_need_nmi = snapshot.prev_need_nmi;
+        self.need_nmi = snapshot.need_nmi;
+        self.rst = snapshot.rst;
+        self.state = snapshot.state;
+        self.stalled = snapshot.stalled;
+        self.port = snapshot.port;
+    }
+
+    // Goes back to the start of the current instruction so that it can be
+    // replayed, keeping any changes made to the pins and the cycle count since
+    // the last cycle. Returns false, dropping the instruction instead, if the
+    // registers were changed.
+    fn rewind(&mut self) -> bool {
+        if let Some(paused) = self.resume.paused {
+            let registers =
+                |s: &Snapshot| (s.a, s.x, s.y, s.pc, s.s, s.p.bits());
+            if registers(&self.save()) != registers(&paused) {
+                self.resume.clear();
+                return false;
+            }
+
+            let drift = self.cycles.wrapping_sub(paused.cycles);
+            self.resume.snapshot.cycles =
+                self.resume.snapshot.cycles.wrapping_add(drift);
+            for (snapshot, _, _) in &mut self.resume.log[..self.resume.len] {
+                snapshot.cycles = snapshot.cycles.wrapping_add(drift);
+            }
+        }
+
+        self.resume.log[self.resume.len - 1].1 = self.pins;
+        let snapshot = self.resume.snapshot;
+        self.restore(&snapshot);
+
+        true
+    }
+
+    #[inline(always)]
+    fn execute(&mut self) -> (Option<u8>, Option<Interrupt>) {
+        self.aborted = None;
+        let interrupt = self.rst || self.prev_need_nmi || self.prev_irq;
+
+        match self.state {
+            RunState::Running => (),
+            // An IRQ ends WAI even if interrupts are disabled, but execution
+            // then continues with the next instruction instead of the handler.
+            RunState::Waiting
+                if interrupt
+                    || (V::INTERRUPT_PINS
+                        && self.pins.irq.is_asserted()
+                        && self.p.contains(Status::I)) =>
+            {
+                self.state = RunState::Running;
+            }
+            RunState::Stopped | RunState::Jammed if self.rst => {
+                self.state = RunState::Running;
+            }
+            RunState::Waiting | RunState::Stopped => {
+                self.dummy_read(self.pc);
+                return (None, None);
+            }
+            // A jammed CPU leaves 0xFFFF on the address bus.
+            RunState::Jammed => {
+                self.dummy_read(0xffff);
+                return (None, None);
+            }
+        }
+
+        if interrupt {
+            let (brk_fn, kind): (Instruction<B, V, T>, _) = if self.rst {
+                // A reset drops any pending interrupts.
+                self.rst = false;
+                self.prev_irq = false;
+                self.irq = false;
+                self.prev_need_nmi = false;
+                self.need_nmi = false;
+                (Cpu::brk::<RST>, Interrupt::Reset)
+            } else if self.prev_need_nmi {
+                self.need_nmi = false;
+                (Cpu::brk::<NMI>, Interrupt::Nmi)
+            } else {
+                (Cpu::brk::<IRQ>, Interrupt::Irq)
+            };
+
+            // The opcode is still fetched, but it's thrown away.
+            self.pins.sync = true;
+            self.read(self.pc, CycleKind::Opcode);
+            self.pins.sync = false;
+            (brk_fn)(self);
+            self.abort();
+
+            (None, Some(kind))
+        } else {
+            self.pins.sync = true;
+            let opcode = self.read(self.pc, CycleKind::Opcode);
+            self.pc = self.pc.wrapping_add(1);
+            self.pins.sync = false;
+            if self.resume.real {
+                self.inspector.instruction(self.pc.wrapping_sub(1), opcode);
+            }
+
+            let opcode_lut = match V::INSTRUCTION_SET {
+                InstructionSet::Nmos => &Cpu::NMOS_OPCODE_LUT,
+                InstructionSet::Cmos => &Cpu::CMOS_OPCODE_LUT,
+                InstructionSet::Rockwell => &Cpu::ROCKWELL_OPCODE_LUT,
+                InstructionSet::Wdc => &Cpu::WDC_OPCODE_LUT,
+            };
+            (opcode_lut[opcode as usize])(self);
+            self.abort();
+
+            (Some(opcode), None)
+        }
+    }
+
+    // Undoes what an instruction did to the registers after a reset aborted
+    // it.
+    fn abort(&mut self) {
+        if let Some(aborted) = self.aborted.take() {
+            self.a = aborted.a;
+            self.x = aborted.x;
+            self.y = aborted.y;
+            self.pc = aborted.pc;
+            self.s = aborted.s;
+            self.p = aborted.p;
+        }
+    }
+
+    /// Resets the CPU like the RST pin does, aborting the current instruction
+    /// and running the reset sequence. A, X and Y are left alone.
+    pub fn reset(&mut self) {
+        self.resume.clear();
+        self.state = RunState::Running;
+        self.rst = true;
+        self.step();
+    }
+
+    /// Puts the CPU back in its power-up state and runs the reset sequence.
+    pub fn power_on(&mut self) {
+        self.a = 0;
+        self.x = 0;
+        self.y = 0;
+        self.s = 0x00;
+        self.p = Status::default();
+        self.pins = Pins::default();
+        self.cycles = 0;
+        self.prev_nmi = false;
+        self.prev_so = false;
+        self.stalled = false;
+        self.port = IoPort::new();
+        self.reset();
+    }
+
+    /// Returns whether the CPU is executing instructions or halted.
+    pub fn run_state(&self) -> RunState {
+        self.state
+    }
+
+    /// Returns whether a JAM opcode locked up the CPU.
+    pub fn is_jammed(&self) -> bool {
+        self.state == RunState::Jammed
+    }
+
+    /// Reads `address` as the CPU would see it, without side effects. Returns
+    /// `None` if the bus can't peek at it.
+    pub fn peek_memory(&self, address: u16) -> Option<u8> {
+        if V::IO_PORT && address <= 0x0001 {
+            // Reading the port lets floating bits fade, so read a copy.
+            let mut port = self.port;
+            return Some(port.read::<V>(address, self.pins.port, self.cycles));
+        }
+
+        self.bus.peek(address & V::ADDRESS_MASK)
+    }
+
+    fn read_byte(&mut self, address: u16) -> u8 {
+        self.read(address, CycleKind::Read)
+    }
+
+    fn dummy_read(&mut self, address: u16) {
+        self.read(address, CycleKind::DummyRead);
+    }
 
-            self.read_byte(self.pc);
-            (brk_fn)(self);
-        } else {
-            let opcode = self.consume_byte();
-            (Cpu::OPCODE_LUT[opcode as usize])(self);
+    // Every instruction inlines the fast path of its accesses, and anything
+    // rare, like RDY stalls, resets or `Cpu::tick` replays, is kept out of
+    // line.
+    #[inline(always)]
+    fn read(&mut self, address: u16, kind: CycleKind) -> u8 {
+        // A reset aborts the current instruction, so the rest of its cycles
+        // never happen.
+        if self.rst {
+            return self.pins.data;
+        }
+        if self.resume.active {
+            return self.replay_read(address, kind);
+        }
+
+        self.stalled = false;
+        self.read_cycle(address, kind);
+        if !self.pins.rdy {
+            return self.stall(address, kind);
         }
+
+        self.pins.data
     }
 
-    fn read_byte(&mut self, address: u16) -> u8 {
+    // Repeats a read for as long as RDY is low.
+    #[cold]
+    #[inline(never)]
+    fn stall(&mut self, address: u16, kind: CycleKind) -> u8 {
+        while !self.pins.rdy {
+            self.stalled = true;
+            self.read_cycle(address, kind);
+        }
+
+        self.pins.data
+    }
+
+    #[inline(always)]
+    fn read_cycle(&mut self, address: u16, kind: CycleKind) {
         self.cycles += 1;
 
-        self.pins.address = address;
+        self.pins.address = address & V::ADDRESS_MASK;
         self.pins.rw = true;
-        self.bus.tick(&mut self.pins);
+        self.pins.kind = kind;
+        self.tick_bus();
+
+        // The bus still sees the cycle, but the I/O port answers it.
+        if V::IO_PORT && address <= 0x0001 {
+            self.pins.data =
+                self.port.read::<V>(address, self.pins.port, self.cycles);
+        }
 
         self.poll_interrupts();
+    }
+
+    // A read while `Cpu::tick` runs an instruction again.
+    #[cold]
+    #[inline(never)]
+    fn replay_read(&mut self, address: u16, kind: CycleKind) -> u8 {
+        if self.resume.paused.is_some() {
+            return self.pins.data;
+        }
+
+        self.stalled = false;
+
+        let mut first = true;
+        loop {
+            match self.next_cycle(first) {
+                Cycle::Replayed => (),
+                Cycle::New => {
+                    self.pins.address = address & V::ADDRESS_MASK;
+                    self.pins.rw = true;
+                    self.pins.kind = kind;
+                    self.tick_bus();
+                    self.log_cycle();
+                }
+                Cycle::Skipped => return self.pins.data,
+            }
+            first = false;
+
+            if V::IO_PORT && address <= 0x0001 {
+                self.pins.data =
+                    self.port.read::<V>(address, self.pins.port, self.cycles);
+            }
+
+            self.poll_interrupts();
+
+            if self.pins.rdy {
+                break;
+            }
+            self.stalled = true;
+        }
 
         self.pins.data
     }
 
+    // Starts a cycle of the current access, which is replayed from the log if
+    // the bus already saw it, or skipped if `Cpu::tick` already ran its cycle.
+    fn next_cycle(&mut self, first: bool) -> Cycle {
+        if first {
+            self.resume.access = self.resume.next;
+            self.resume.next += 1;
+
+            if self.resume.access < self.resume.len {
+                let (snapshot, pins, done) =
+                    self.resume.log[self.resume.access];
+                self.restore(&snapshot);
+                self.pins = pins;
+                // A stalled access that finishes now is new to the bus.
+                self.resume.real = !done;
+                return Cycle::Replayed;
+            }
+        }
+
+        if self.resume.limited && self.resume.ticked {
+            self.resume.paused = Some(self.save());
+            self.resume.real = false;
+            return Cycle::Skipped;
+        }
+
+        self.cycles += 1;
+        Cycle::New
+    }
+
+    #[inline(always)]
+    fn tick_bus(&mut self) {
+        self.pins.wait = 0;
+        self.bus.tick(&mut self.pins);
+        self.cycles += self.pins.wait as u64;
+        self.inspector.access(&self.pins);
+    }
+
+    // Records a new cycle that `Cpu::tick` ran in the log.
+    fn log_cycle(&mut self) {
+        self.resume.real = true;
+
+        if self.resume.limited {
+            let done = !self.pins.rw || self.pins.rdy;
+            let entry = (self.save(), self.pins, done);
+            self.resume.log[self.resume.access] = entry;
+            self.resume.len = self.resume.access + 1;
+            self.resume.ticked = true;
+        }
+    }
+
     fn read_word(&mut self, address: u16) -> u16 {
         let low = self.read_byte(address);
         let high = self.read_byte(address.wrapping_add(1));
@@ -416,64 +1373,124 @@ where
     }
 
     fn write_byte(&mut self, address: u16, data: u8) {
+        self.write(address, data, CycleKind::Write);
+    }
+
+    #[inline(always)]
+    fn write(&mut self, address: u16, data: u8, kind: CycleKind) {
+        if self.rst {
+            return;
+        }
+        if self.resume.active {
+            return self.replay_write(address, data, kind);
+        }
+
         self.cycles += 1;
+        self.write_cycle(address, data, kind);
+        self.poll_interrupts();
+    }
 
-        self.pins.address = address;
-        self.pins.data = data;
-        self.pins.rw = false;
-        self.bus.tick(&mut self.pins);
+    // A write while `Cpu::tick` runs an instruction again.
+    #[cold]
+    #[inline(never)]
+    fn replay_write(&mut self, address: u16, data: u8, kind: CycleKind) {
+        if self.resume.paused.is_some() {
+            return;
+        }
+
+        match self.next_cycle(true) {
+            Cycle::Replayed => (),
+            Cycle::New => {
+                self.write_cycle(address, data, kind);
+                self.log_cycle();
+            }
+            Cycle::Skipped => return,
+        }
 
         self.poll_interrupts();
     }
 
+    #[inline(always)]
+    fn write_cycle(&mut self, address: u16, data: u8, kind: CycleKind) {
+        if V::IO_PORT && address <= 0x0001 {
+            self.port.write::<V>(address, data, self.cycles);
+            self.pins.port = self.port.lines();
+        }
+
+        self.pins.address = address & V::ADDRESS_MASK;
+        self.pins.data = data;
+        self.pins.rw = false;
+        self.pins.kind = kind;
+        self.tick_bus();
+    }
+
     fn consume_byte(&mut self) -> u8 {
-        let data = self.read_byte(self.pc);
+        let data = self.read(self.pc, CycleKind::Operand);
         self.pc = self.pc.wrapping_add(1);
         data
     }
 
     fn consume_word(&mut self) -> u16 {
-        let data = self.read_word(self.pc);
-        self.pc = self.pc.wrapping_add(2);
-        data
+        let low = self.consume_byte();
+        let high = self.consume_byte();
+        (high as u16) << 8 | low as u16
     }
 
-    fn peek(&mut self) -> u8 {
-        self.read_byte(STACK_BASE + self.s as u16)
+    fn peek(&mut self) {
+        self.dummy_read(STACK_BASE + self.s as u16);
     }
 
     fn push(&mut self, data: u8) {
-        self.write_byte(STACK_BASE + self.s as u16, data);
+        self.write(STACK_BASE + self.s as u16, data, CycleKind::Push);
         self.s = self.s.wrapping_sub(1);
     }
 
     fn pop(&mut self) -> u8 {
         self.s = self.s.wrapping_add(1);
-        self.read_byte(STACK_BASE + self.s as u16)
+        self.read(STACK_BASE + self.s as u16, CycleKind::Pull)
     }
 
+    #[inline(always)]
     fn poll_interrupts(&mut self) {
         // We need to track the previous status of the interrupt pins because
         // their statuses at the end of the second-to-last cycle determine if
         // the next instruction will be an interrupt.
+        let irq = V::INTERRUPT_PINS && self.pins.irq.is_asserted();
+        let nmi = V::INTERRUPT_PINS && self.pins.nmi.is_asserted();
+
         self.prev_irq = self.irq;
-        self.irq = self.pins.irq && !self.p.contains(Status::I);
+        self.irq = irq && !self.p.contains(Status::I);
 
         self.prev_need_nmi = self.need_nmi;
 
         // An NMI is raised if the NMI pin goes from inactive during one cycle
         // to active during the next. The NMI stays "raised" until it's
         // handled.
-        if !self.prev_nmi && self.pins.nmi {
+        if !self.prev_nmi && nmi {
             self.need_nmi = true;
         }
-        self.prev_nmi = self.pins.nmi;
+        self.prev_nmi = nmi;
+
+        // Like NMI, SO is edge sensitive, but it sets the V flag directly.
+        let so = V::SO_PIN && self.pins.so;
+        if !self.prev_so && so {
+            self.p.insert(Status::V);
+        }
+        self.prev_so = so;
 
         if !self.rst && self.pins.rst {
-            self.rst = self.pins.rst;
+            self.start_reset();
         }
     }
 
+    // RST was just asserted.
+    #[cold]
+    #[inline(never)]
+    fn start_reset(&mut self) {
+        self.rst = true;
+        self.aborted = Some(self.save());
+    }
+
     fn set_a(&mut self, value: u8) {
         self.a = value;
         self.p.set(Status::Z, self.a == 0);
@@ -493,12 +1510,43 @@ where
     }
 }
 
-// Instruction helpers
+impl<B, V> Cpu<B, V>
+where
+    B: Bus,
+    V: Variant,
+{
+    /// Constructs a new `Cpu` of the given variant in a power-up state.
+    pub fn with_variant(bus: B, variant: V) -> Cpu<B, V> {
+        Cpu::with_inspector(bus, variant, NoInspector)
+    }
+}
+
 impl<B> Cpu<B>
 where
     B: Bus,
+{
+    /// Constructs a new NMOS `Cpu` in a power-up state.
+    pub fn new(bus: B) -> Cpu<B> {
+        Cpu::with_variant(bus, Nmos)
+    }
+}
+
+// Instruction helpers
+impl<B, V, T> Cpu<B, V, T>
+where
+    B: Bus,
+    V: Variant,
+    T: Inspector,
 {
     fn add(&mut self, value: u8) {
+        if V::DECIMAL_MODE && self.p.contains(Status::D) {
+            self.add_decimal(value);
+        } else {
+            self.add_binary(value);
+        }
+    }
+
+    fn add_binary(&mut self, value: u8) {
         let a = self.a;
         let result = (self.a as u16)
             .wrapping_add(value as u16)
@@ -512,19 +1560,122 @@ where
         self.p.set(Status::N, self.a & 0x80 != 0);
     }
 
+    fn add_decimal(&mut self, value: u8) {
+        let a = self.a;
+        let carry = self.p.contains(Status::C) as u8;
+
+        let mut low = (a & 0x0f) + (value & 0x0f) + carry;
+        if low > 0x09 {
+            low += 0x06;
+        }
+        let mut high = (a >> 4) + (value >> 4) + (low > 0x0f) as u8;
+
+        // The NMOS 6502 sets the N and V flags from the intermediate result,
+        // i.e., before the high nibble is adjusted, and the Z flag from the
+        // binary sum.
+        self.p.set(Status::N, high & 0x08 != 0);
+        self.p
+            .set(Status::V, !(a ^ value) & (a ^ (high << 4)) & 0x80 != 0);
+        self.p
+            .set(Status::Z, a.wrapping_add(value).wrapping_add(carry) == 0);
+
+        if high > 0x09 {
+            high += 0x06;
+        }
+        self.p.set(Status::C, high > 0x0f);
+
+        self.a = (high << 4) | (low & 0x0f);
+
+        if Self::CMOS {
+            self.set_decimal_flags();
+        }
+    }
+
+    fn subtract(&mut self, value: u8) {
+        if V::DECIMAL_MODE && self.p.contains(Status::D) {
+            self.subtract_decimal(value);
+        } else {
+            // If we reformulate subtraction as addition, then we can use the
+            // same logic for ADC and SBC. All we need to do is make our value
+            // from memory negative, i.e., invert it.
+            self.add_binary(value ^ 0xff);
+        }
+    }
+
+    fn subtract_decimal(&mut self, value: u8) {
+        let borrow = !self.p.contains(Status::C) as i16;
+
+        let mut low = (self.a & 0x0f) as i16 - (value & 0x0f) as i16 - borrow;
+        let result = if Self::CMOS {
+            // The CMOS chips adjust the binary difference instead of putting
+            // it together a nibble at a time.
+            let mut result = self.a as i16 - value as i16 - borrow;
+            if result < 0 {
+                result -= 0x60;
+            }
+            if low < 0 {
+                result -= 0x06;
+            }
+            result
+        } else {
+            if low < 0 {
+                low = ((low - 0x06) & 0x0f) - 0x10;
+            }
+            let mut result =
+                (self.a & 0xf0) as i16 - (value & 0xf0) as i16 + low;
+            if result < 0 {
+                result -= 0x60;
+            }
+            result
+        };
+
+        // All of the flags are set as if it were a binary subtraction.
+        self.add_binary(value ^ 0xff);
+        self.a = result as u8;
+
+        if Self::CMOS {
+            self.set_decimal_flags();
+        }
+    }
+
+    fn set_decimal_flags(&mut self) {
+        // The CMOS chips take an extra cycle to set the N and Z flags from
+        // the decimal result.
+        self.dummy_read(self.pc);
+        self.p.set(Status::Z, self.a == 0);
+        self.p.set(Status::N, self.a & 0x80 != 0);
+    }
+
     fn branch(&mut self, condition: bool) {
         let offset = self.consume_byte() as i8 as u16;
         if condition {
-            self.read_byte(self.pc);
+            let new_irq = self.irq && !self.prev_irq;
+            let new_nmi = self.need_nmi && !self.prev_need_nmi;
+
+            self.dummy_read(self.pc);
 
             let old_pc = self.pc;
             self.pc = self.pc.wrapping_add(offset);
 
             if old_pc & 0xff00 != self.pc & 0xff00 {
-                self.read_byte(
-                    (old_pc & 0xff00)
-                        | (old_pc as u8).wrapping_add(offset as u8) as u16,
-                );
+                if Self::CMOS {
+                    self.dummy_read(old_pc);
+                } else {
+                    self.dummy_read(
+                        (old_pc & 0xff00)
+                            | (old_pc as u8).wrapping_add(offset as u8) as u16,
+                    );
+                }
+            } else {
+                // A taken branch that doesn't cross a page doesn't poll for
+                // interrupts during its last cycle, so an interrupt detected
+                // during the operand fetch waits for the next instruction.
+                if new_irq {
+                    self.prev_irq = false;
+                }
+                if new_nmi {
+                    self.prev_need_nmi = false;
+                }
             }
         }
     }
@@ -538,6 +1689,15 @@ where
     }
 
     fn modify<const I: u8>(&mut self, value: u8) -> u8 {
+        if I == TRB || I == TSB {
+            self.p.set(Status::Z, self.a & value == 0);
+            return if I == TRB {
+                value & !self.a
+            } else {
+                value | self.a
+            };
+        }
+
         let (result, carry) = match I {
             ASL | SLO => (value.wrapping_shl(1), value & 0x80 != 0),
             DCP | DEC => (value.wrapping_sub(1), self.p.contains(Status::C)),
@@ -547,6 +1707,11 @@ where
                 value.wrapping_shl(1) | self.p.contains(Status::C) as u8,
                 value & 0x80 != 0,
             ),
+            // Chips without ROR shift left instead, but leave the carry
+            // alone.
+            ROR | RRA if !V::ROR => {
+                (value.wrapping_shl(1), self.p.contains(Status::C))
+            }
             ROR | RRA => (
                 (self.p.contains(Status::C) as u8) << 7
                     | value.wrapping_shr(1),
@@ -562,24 +1727,59 @@ where
         result
     }
 
+    fn modify_bit<const N: u8, const SET: bool>(&mut self) {
+        let address = self.consume_byte() as u16;
+        self.pins.ml = V::VP_AND_ML_PINS;
+        let value = self.read_byte(address);
+        self.dummy_read(address);
+
+        let result = if SET {
+            value | (1 << N)
+        } else {
+            value & !(1 << N)
+        };
+        self.write_byte(address, result);
+        self.pins.ml = false;
+    }
+
+    fn read_test_bit(&mut self) -> u8 {
+        let address = self.consume_byte() as u16;
+        let value = self.read_byte(address);
+        self.dummy_read(address);
+        value
+    }
+
     fn read_modify_write<const M: u8, const I: u8>(&mut self) -> u8 {
         if M == ACCUMULATOR {
-            self.read_byte(self.pc);
+            self.dummy_read(self.pc);
             self.a = self.modify::<I>(self.a);
             self.a
         } else {
             // Treat it as a write instruction while fetching the effective
-            // address to get the cycle count right.
-            let effective_address = self.effective_address::<M, true>();
+            // address to get the cycle count right. The CMOS chips skip the
+            // extra cycle for shifts and rotates that don't cross a page.
+            let effective_address =
+                if Self::CMOS && M == ABSOLUTE_X && I != INC && I != DEC {
+                    self.effective_address::<M, false>()
+                } else {
+                    self.effective_address::<M, true>()
+                };
+            self.pins.ml = V::VP_AND_ML_PINS;
             let value = self.read_byte(effective_address);
 
-            // Read-Modify-Write instructions have an extra write since it
-            // takes an extra cycle to modify the value.
-            self.write_byte(effective_address, value);
+            // Read-Modify-Write instructions take an extra cycle to modify
+            // the value. The NMOS chips write the unmodified value back
+            // during it, while the CMOS chips read it again.
+            if Self::CMOS {
+                self.dummy_read(effective_address);
+            } else {
+                self.write(effective_address, value, CycleKind::DummyWrite);
+            }
 
             let result = self.modify::<I>(value);
 
             self.write_byte(effective_address, result);
+            self.pins.ml = false;
 
             result
         }
@@ -604,11 +1804,16 @@ where
                 // instructions always have the extra read since they can't
                 // undo a write to an invalid address.
                 if page_cross || W {
-                    self.read_byte((high as u16) << 8 | low as u16);
+                    self.indexing_dummy_read((high as u16) << 8 | low as u16);
                 }
 
                 effective_address
             }
+            ABSOLUTE_X_INDIRECT => {
+                let ptr = self.consume_word().wrapping_add(self.x as u16);
+                self.dummy_read(self.pc.wrapping_sub(1));
+                self.read_word(ptr)
+            }
             IMMEDIATE => {
                 let effective_address = self.pc;
                 self.pc = self.pc.wrapping_add(1);
@@ -616,11 +1821,17 @@ where
             }
             INDIRECT => {
                 let ptr = self.consume_word();
-                self.read_word_bugged(ptr)
+                if V::JMP_INDIRECT_BUG {
+                    self.read_word_bugged(ptr)
+                } else {
+                    // Fixing the bug costs an extra cycle.
+                    self.dummy_read(self.pc.wrapping_sub(1));
+                    self.read_word(ptr)
+                }
             }
             INDEXED_INDIRECT => {
                 let ptr = self.consume_byte();
-                self.read_byte(ptr as u16);
+                self.indexing_dummy_read(ptr as u16);
                 self.read_word_bugged(ptr.wrapping_add(self.x) as u16)
             }
             INDIRECT_INDEXED => {
@@ -639,29 +1850,84 @@ where
                 // instructions always have the extra read since they can't
                 // undo a write to an invalid address.
                 if did_cross_page || W {
-                    self.read_byte((high as u16) << 8 | low as u16);
+                    self.indexing_dummy_read((high as u16) << 8 | low as u16);
                 }
 
                 effective_address
             }
             ZERO_PAGE => self.consume_byte() as u16,
+            ZERO_PAGE_INDIRECT => {
+                let ptr = self.consume_byte();
+                self.read_word_bugged(ptr as u16)
+            }
             ZERO_PAGE_X | ZERO_PAGE_Y => {
                 let index = if M == ZERO_PAGE_X { self.x } else { self.y };
 
                 let address = self.consume_byte();
-                self.read_byte(address as u16);
+                self.indexing_dummy_read(address as u16);
 
                 address.wrapping_add(index) as u16
             }
             _ => unreachable!("unexpected addressing mode: {}", M),
         }
     }
+
+    // SHA, SHX, SHY and TAS store a value ANDed with the high byte of an
+    // address plus one. The AND drops out if RDY stalls the cycle before the
+    // write.
+    fn store_high_byte_and<const M: u8>(&mut self, value: u8) {
+        let effective_address = self.effective_address::<M, true>();
+        let low_byte = effective_address & 0x00ff;
+
+        let (address, value) = match self.unstable.sh {
+            ShModel::PageCross => {
+                let index = if M == ABSOLUTE_X { self.x } else { self.y };
+                let base = effective_address.wrapping_sub(index as u16);
+                let high_byte = (base >> 8) as u8;
+                let value = value & self.unstable_high_byte(high_byte);
+
+                if base & 0xff00 == effective_address & 0xff00 {
+                    (effective_address, value)
+                } else {
+                    ((value as u16) << 8 | low_byte, value)
+                }
+            }
+            ShModel::Always => {
+                let high_byte = (effective_address >> 8) as u8;
+                let value = value & self.unstable_high_byte(high_byte);
+                ((value as u16) << 8 | low_byte, value)
+            }
+        };
+
+        self.write_byte(address, value);
+    }
+
+    fn unstable_high_byte(&self, high_byte: u8) -> u8 {
+        if self.stalled {
+            0xff
+        } else {
+            high_byte.wrapping_add(1)
+        }
+    }
+
+    fn indexing_dummy_read(&mut self, address: u16) {
+        // The NMOS chips read from whatever address is on hand while indexing,
+        // which can be invalid. The CMOS chips read the last instruction byte
+        // again instead.
+        if Self::CMOS {
+            self.dummy_read(self.pc.wrapping_sub(1));
+        } else {
+            self.dummy_read(address);
+        }
+    }
 }
 
 // Instructions
-impl<B> Cpu<B>
+impl<B, V, T> Cpu<B, V, T>
 where
     B: Bus,
+    V: Variant,
+    T: Inspector,
 {
     fn adc<const M: u8>(&mut self) {
         let effective_address = self.effective_address::<M, false>();
@@ -697,30 +1963,63 @@ where
     fn ane<const M: u8>(&mut self) {
         let effective_address = self.effective_address::<M, false>();
 
-        // Treat ANE as a NOP since it's unstable.
-        self.read_byte(effective_address);
+        let operand = self.read_byte(effective_address);
+        if let Some(magic) = self.unstable.ane_magic {
+            self.set_a((self.a | magic) & self.x & operand);
+        }
     }
 
     fn arr<const M: u8>(&mut self) {
         let effective_address = self.effective_address::<M, false>();
 
-        self.a &= self.read_byte(effective_address);
+        let value = self.a & self.read_byte(effective_address);
         self.set_a(
-            (self.p.contains(Status::C) as u8) << 7 | self.a.wrapping_shr(1),
+            (self.p.contains(Status::C) as u8) << 7 | value.wrapping_shr(1),
         );
 
-        // TODO: Explain how the carry and overflow flag are set.
-        self.p.set(Status::C, self.a & 0x40 != 0);
-        self.p.set(
-            Status::V,
-            ((self.p.contains(Status::C) as u8) ^ ((self.a >> 5) & 0x01)) != 0,
-        );
+        if V::DECIMAL_MODE && self.p.contains(Status::D) {
+            // ARR uses the ADC circuitry to fix up the rotated value, so in
+            // decimal mode each nibble is adjusted based on the unrotated
+            // value. The N and Z flags reflect the result before the fix up.
+            let low = value & 0x0f;
+            let high = value >> 4;
+
+            self.p.set(Status::V, (value ^ self.a) & 0x40 != 0);
+
+            if low + (low & 0x01) > 0x05 {
+                self.a = (self.a & 0xf0) | (self.a.wrapping_add(0x06) & 0x0f);
+            }
+
+            let carry = high + (high & 0x01) > 0x05;
+            if carry {
+                self.a = self.a.wrapping_add(0x60);
+            }
+            self.p.set(Status::C, carry);
+        } else {
+            // TODO: Explain how the carry and overflow flag are set.
+            self.p.set(Status::C, self.a & 0x40 != 0);
+            self.p.set(
+                Status::V,
+                ((self.p.contains(Status::C) as u8) ^ ((self.a >> 5) & 0x01))
+                    != 0,
+            );
+        }
     }
 
     fn asl<const M: u8>(&mut self) {
         self.read_modify_write::<M, ASL>();
     }
 
+    fn bbr<const N: u8>(&mut self) {
+        let value = self.read_test_bit();
+        self.branch(value & (1 << N) == 0);
+    }
+
+    fn bbs<const N: u8>(&mut self) {
+        let value = self.read_test_bit();
+        self.branch(value & (1 << N) != 0);
+    }
+
     fn bcc(&mut self) {
         self.branch(!self.p.contains(Status::C));
     }
@@ -739,8 +2038,12 @@ where
         let value = self.read_byte(effective_address);
 
         self.p.set(Status::Z, self.a & value == 0);
-        self.p.set(Status::V, value & Status::V.bits() != 0);
-        self.p.set(Status::N, value & Status::N.bits() != 0);
+        // There's no memory location to test the high bits of in immediate
+        // mode, so only the Z flag is affected.
+        if M != IMMEDIATE {
+            self.p.set(Status::V, value & Status::V.bits() != 0);
+            self.p.set(Status::N, value & Status::N.bits() != 0);
+        }
     }
 
     fn bmi(&mut self) {
@@ -755,12 +2058,17 @@ where
         self.branch(!self.p.contains(Status::N));
     }
 
+    fn bra(&mut self) {
+        self.branch(true);
+    }
+
     fn brk<const I: u8>(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         if I == BRK {
             self.pc += 1;
         }
 
+        let mut hijacked = false;
         if I == RST {
             self.peek();
             self.s = self.s.wrapping_sub(1);
@@ -771,20 +2079,50 @@ where
         } else {
             self.push((self.pc >> 8) as u8);
             self.push(self.pc as u8);
+
+            // The vector isn't chosen until after the program counter is
+            // pushed, so an NMI detected by then hijacks a BRK or IRQ. The
+            // pushed status is unaffected, i.e., the B flag still tells
+            // software whether it was a BRK.
+            if V::INTERRUPT_HIJACKING && I != NMI && self.need_nmi {
+                self.need_nmi = false;
+                hijacked = true;
+            }
+
             let p = if I == BRK { self.p | Status::B } else { self.p };
             self.push(p.bits());
         }
 
-        // TODO: Implement interrupt hijacking.
         // TODO: Should NMI not set the I flag?
         self.p.insert(Status::I);
+        if V::INTERRUPTS_CLEAR_DECIMAL {
+            self.p.remove(Status::D);
+        }
         let vector = match I {
+            BRK | IRQ if hijacked => NMI_VECTOR,
             BRK | IRQ => IRQ_VECTOR,
             NMI => NMI_VECTOR,
             RST => RESET_VECTOR,
             _ => unreachable!("unexpected interrupt type: {}", I),
         };
-        self.pc = self.read_word(vector);
+        self.pins.vp = V::VP_AND_ML_PINS;
+        let low = self.read(vector, CycleKind::Vector);
+        let high = self.read(vector.wrapping_add(1), CycleKind::Vector);
+        self.pc = (high as u16) << 8 | low as u16;
+        self.pins.vp = false;
+
+        if self.resume.real && !self.rst {
+            let interrupt = match I {
+                _ if hijacked => Some(Interrupt::Nmi),
+                BRK => None,
+                IRQ => Some(Interrupt::Irq),
+                NMI => Some(Interrupt::Nmi),
+                _ => Some(Interrupt::Reset),
+            };
+            if let Some(interrupt) = interrupt {
+                self.inspector.interrupt(interrupt, vector);
+            }
+        }
     }
 
     fn bvc(&mut self) {
@@ -796,22 +2134,22 @@ where
     }
 
     fn clc(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.p.remove(Status::C);
     }
 
     fn cld(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.p.remove(Status::D);
     }
 
     fn cli(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.p.remove(Status::I);
     }
 
     fn clv(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.p.remove(Status::V);
     }
 
@@ -846,12 +2184,12 @@ where
     }
 
     fn dex(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.set_x(self.x.wrapping_sub(1));
     }
 
     fn dey(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.set_y(self.y.wrapping_sub(1));
     }
 
@@ -866,23 +2204,24 @@ where
     }
 
     fn inx(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.set_x(self.x.wrapping_add(1));
     }
 
     fn iny(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.set_y(self.y.wrapping_add(1));
     }
 
     fn isb<const M: u8>(&mut self) {
         let result = self.read_modify_write::<M, ISB>();
-        self.add(result ^ 0xff);
+        self.subtract(result);
     }
 
     fn jam(&mut self) {
-        // Treat JAM as a one byte NOP.
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
+        self.dummy_read(0xffff);
+        self.state = RunState::Jammed;
     }
 
     fn jmp<const M: u8>(&mut self) {
@@ -939,20 +2278,32 @@ where
     fn lxa<const M: u8>(&mut self) {
         let effective_address = self.effective_address::<M, false>();
 
-        // This instruction should perform a bitwise AND between a constant and
-        // the operand before storing the result. The constant is unreliable
-        // though. To remove uncertainty, we have the constant always be 0xff,
-        // removing the need for the bitwise AND.
-        self.a = self.read_byte(effective_address);
+        let operand = self.read_byte(effective_address);
+        self.a = (self.a | self.unstable.lxa_magic) & operand;
         self.set_x(self.a);
     }
 
     fn nop<const M: u8>(&mut self) {
         if M == IMPLIED {
-            self.read_byte(self.pc);
+            self.dummy_read(self.pc);
         } else {
             let effective_address = self.effective_address::<M, false>();
-            self.read_byte(effective_address);
+            self.dummy_read(effective_address);
+        }
+    }
+
+    fn nop1(&mut self) {
+        // The unused CMOS opcodes in columns 3 and B are NOPs that finish
+        // after the opcode fetch.
+    }
+
+    fn nop8(&mut self) {
+        // The CMOS opcode 0x5c is a three byte NOP that reads from 0xffxx,
+        // where xx is the low byte of its operand, and then stays there for
+        // four more cycles.
+        let address = 0xff00 | (self.consume_word() & 0x00ff);
+        for _ in 0..5 {
+            self.dummy_read(address);
         }
     }
 
@@ -963,35 +2314,63 @@ where
     }
 
     fn pha(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.push(self.a);
     }
 
     fn php(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.push((self.p | Status::B | Status::U).bits());
     }
 
+    fn phx(&mut self) {
+        self.dummy_read(self.pc);
+        self.push(self.x);
+    }
+
+    fn phy(&mut self) {
+        self.dummy_read(self.pc);
+        self.push(self.y);
+    }
+
     fn pla(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.peek();
         let value = self.pop();
         self.set_a(value);
     }
 
     fn plp(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.peek();
         self.p = (Status::from_bits_truncate(self.pop())
             & !(Status::B | Status::U))
             | (self.p & (Status::B | Status::U));
     }
 
+    fn plx(&mut self) {
+        self.dummy_read(self.pc);
+        self.peek();
+        let value = self.pop();
+        self.set_x(value);
+    }
+
+    fn ply(&mut self) {
+        self.dummy_read(self.pc);
+        self.peek();
+        let value = self.pop();
+        self.set_y(value);
+    }
+
     fn rla<const M: u8>(&mut self) {
         let result = self.read_modify_write::<M, RLA>();
         self.set_a(self.a & result);
     }
 
+    fn rmb<const N: u8>(&mut self) {
+        self.modify_bit::<N, false>();
+    }
+
     fn rol<const M: u8>(&mut self) {
         self.read_modify_write::<M, ROL>();
     }
@@ -1006,7 +2385,7 @@ where
     }
 
     fn rti(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.peek();
         self.p = (Status::from_bits_truncate(self.pop())
             & !(Status::B | Status::U))
@@ -1017,12 +2396,12 @@ where
     }
 
     fn rts(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.peek();
         let pcl = self.pop();
         let pch = self.pop();
         self.pc = (pch as u16) << 8 | pcl as u16;
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.pc = self.pc.wrapping_add(1);
     }
 
@@ -1035,11 +2414,8 @@ where
     fn sbc<const M: u8>(&mut self) {
         let effective_address = self.effective_address::<M, false>();
 
-        // If we reformulate subtraction as addition, then we can use the same
-        // logic for ADC and SBC. All we need to do is make our value from
-        // memory negative, i.e., invert it.
-        let value = self.read_byte(effective_address) ^ 0xff;
-        self.add(value);
+        let value = self.read_byte(effective_address);
+        self.subtract(value);
     }
 
     fn sbx<const M: u8>(&mut self) {
@@ -1053,62 +2429,30 @@ where
     }
 
     fn sec(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.p.insert(Status::C);
     }
 
     fn sed(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.p.insert(Status::D);
     }
 
     fn sei(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.p.insert(Status::I);
     }
 
     fn sha<const M: u8>(&mut self) {
-        let effective_address = self.effective_address::<M, true>();
-
-        let high_byte = (effective_address & 0xff00) >> 8;
-        let low_byte = effective_address & 0x00ff;
-        let value = self.a & self.x & (high_byte as u8).wrapping_add(1);
-
-        // https://forums.nesdev.org/viewtopic.php?f=3&t=3831&start=30
-        self.write_byte(
-            ((self.a as u16 & self.x as u16 & (high_byte.wrapping_add(1)))
-                << 8)
-                | low_byte,
-            value,
-        );
+        self.store_high_byte_and::<M>(self.a & self.x);
     }
 
     fn shx<const M: u8>(&mut self) {
-        let effective_address = self.effective_address::<M, true>();
-
-        let high_byte = (effective_address & 0xff00) >> 8;
-        let low_byte = effective_address & 0x00ff;
-        let value = self.x & (high_byte as u8).wrapping_add(1);
-
-        // https://forums.nesdev.org/viewtopic.php?f=3&t=3831&start=30
-        self.write_byte(
-            ((self.x as u16 & (high_byte.wrapping_add(1))) << 8) | low_byte,
-            value,
-        );
+        self.store_high_byte_and::<M>(self.x);
     }
 
     fn shy<const M: u8>(&mut self) {
-        let effective_address = self.effective_address::<M, true>();
-
-        let high_byte = (effective_address & 0xff00) >> 8;
-        let low_byte = effective_address & 0x00ff;
-        let value = self.y & (high_byte as u8).wrapping_add(1);
-
-        // https://forums.nesdev.org/viewtopic.php?f=3&t=3831&start=30
-        self.write_byte(
-            ((self.y as u16 & (high_byte.wrapping_add(1))) << 8) | low_byte,
-            value,
-        );
+        self.store_high_byte_and::<M>(self.y);
     }
 
     fn slo<const M: u8>(&mut self) {
@@ -1121,12 +2465,22 @@ where
         self.set_a(self.a ^ result);
     }
 
+    fn smb<const N: u8>(&mut self) {
+        self.modify_bit::<N, true>();
+    }
+
     fn sta<const M: u8>(&mut self) {
         let effective_address = self.effective_address::<M, true>();
 
         self.write_byte(effective_address, self.a);
     }
 
+    fn stp(&mut self) {
+        self.dummy_read(self.pc);
+        self.dummy_read(self.pc);
+        self.state = RunState::Stopped;
+    }
+
     fn stx<const M: u8>(&mut self) {
         let effective_address = self.effective_address::<M, true>();
 
@@ -1139,50 +2493,58 @@ where
         self.write_byte(effective_address, self.y);
     }
 
-    fn tas<const M: u8>(&mut self) {
+    fn stz<const M: u8>(&mut self) {
         let effective_address = self.effective_address::<M, true>();
 
-        let high_byte = (effective_address & 0xff00) >> 8;
-        let low_byte = effective_address & 0x00ff;
-        let value = self.a & self.x & (high_byte as u8).wrapping_add(1);
-        self.s = self.a & self.x;
+        self.write_byte(effective_address, 0);
+    }
 
-        // https://forums.nesdev.org/viewtopic.php?f=3&t=3831&start=30
-        self.write_byte(
-            ((self.a as u16 & self.x as u16 & (high_byte.wrapping_add(1)))
-                << 8)
-                | low_byte,
-            value,
-        );
+    fn tas<const M: u8>(&mut self) {
+        self.s = self.a & self.x;
+        self.store_high_byte_and::<M>(self.s);
     }
 
     fn tax(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.set_x(self.a);
     }
 
     fn tay(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.set_y(self.a);
     }
 
+    fn trb<const M: u8>(&mut self) {
+        self.read_modify_write::<M, TRB>();
+    }
+
+    fn tsb<const M: u8>(&mut self) {
+        self.read_modify_write::<M, TSB>();
+    }
+
     fn tsx(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.set_x(self.s);
     }
 
     fn txa(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.set_a(self.x);
     }
 
     fn txs(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.s = self.x;
     }
 
     fn tya(&mut self) {
-        self.read_byte(self.pc);
+        self.dummy_read(self.pc);
         self.set_a(self.y);
     }
+
+    fn wai(&mut self) {
+        self.dummy_read(self.pc);
+        self.dummy_read(self.pc);
+        self.state = RunState::Waiting;
+    }
 }
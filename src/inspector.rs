@@ -0,0 +1,26 @@
+use crate::{Interrupt, Pins};
+
+/// Hooks for observing a `Cpu` as it runs.
+///
+/// Every hook does nothing by default, so an inspector only needs to
+/// implement the ones it cares about. The hooks only see bus cycles as they
+/// happen, i.e., they aren't called again when [`Cpu::tick`](crate::Cpu::tick)
+/// replays an instruction.
+pub trait Inspector {
+    /// Called after the opcode of an instruction at `pc` is fetched.
+    fn instruction(&mut self, _pc: u16, _opcode: u8) {}
+
+    /// Called after each bus cycle.
+    fn access(&mut self, _pins: &Pins) {}
+
+    /// Called after the vector of an interrupt sequence is fetched. The
+    /// vector can differ from the usual one for `interrupt` if an NMI
+    /// hijacked the sequence.
+    fn interrupt(&mut self, _interrupt: Interrupt, _vector: u16) {}
+}
+
+/// An [`Inspector`] that does nothing.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoInspector;
+
+impl Inspector for NoInspector {}
@@ -0,0 +1,117 @@
+/// A device that can assert an interrupt line, identified by a number less
+/// than [`Source::MAX`] and optionally named for debuggers.
+///
+/// Sources are compared by ID alone, since that's all a line keeps.
+#[derive(Clone, Copy, Debug)]
+pub struct Source {
+    id: u8,
+    name: Option<&'static str>,
+}
+
+impl Source {
+    /// The number of sources an [`InterruptLine`] can tell apart.
+    pub const MAX: u8 = 32;
+
+    /// Constructs a new `Source` with the given ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not less than [`Source::MAX`].
+    pub const fn new(id: u8) -> Source {
+        assert!(id < Source::MAX, "interrupt source ID out of range");
+        Source { id, name: None }
+    }
+
+    /// Constructs a new `Source` with the given ID and name, like
+    /// `Source::named(0, "VIA1")`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not less than [`Source::MAX`].
+    pub const fn named(id: u8, name: &'static str) -> Source {
+        assert!(id < Source::MAX, "interrupt source ID out of range");
+        Source {
+            id,
+            name: Some(name),
+        }
+    }
+
+    /// Returns the ID of the source.
+    pub const fn id(self) -> u8 {
+        self.id
+    }
+
+    /// Returns the name of the source, if it has one.
+    pub const fn name(self) -> Option<&'static str> {
+        self.name
+    }
+}
+
+impl PartialEq for Source {
+    fn eq(&self, other: &Source) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Source {}
+
+/// A wired-OR interrupt line, which stays asserted for as long as any source
+/// holds it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InterruptLine {
+    sources: u32,
+}
+
+impl InterruptLine {
+    /// Asserts the line on behalf of `source`.
+    pub fn assert(&mut self, source: Source) {
+        self.sources |= 1 << source.id;
+    }
+
+    /// Stops `source` from asserting the line. The line stays asserted if
+    /// other sources still hold it.
+    pub fn release(&mut self, source: Source) {
+        self.sources &= !(1 << source.id);
+    }
+
+    /// Asserts or releases the line on behalf of `source`.
+    pub fn set(&mut self, source: Source, asserted: bool) {
+        if asserted {
+            self.assert(source);
+        } else {
+            self.release(source);
+        }
+    }
+
+    /// Returns whether any source is asserting the line.
+    pub fn is_asserted(&self) -> bool {
+        self.sources != 0
+    }
+
+    /// Returns whether `source` is asserting the line.
+    pub fn is_asserted_by(&self, source: Source) -> bool {
+        self.sources & (1 << source.id) != 0
+    }
+
+    /// Returns the sources asserting the line. These only have IDs; use
+    /// [`InterruptLine::sources_among`] to get their names.
+    pub fn sources(&self) -> impl Iterator<Item = Source> {
+        let sources = self.sources;
+        (0..Source::MAX)
+            .filter(move |id| sources & (1 << id) != 0)
+            .map(Source::new)
+    }
+
+    /// Returns the sources in `known` that are asserting the line, names and
+    /// all.
+    pub fn sources_among<'a>(
+        &self,
+        known: &'a [Source],
+    ) -> impl Iterator<Item = Source> + 'a {
+        let line = *self;
+        known
+            .iter()
+            .copied()
+            .filter(move |&source| line.is_asserted_by(source))
+    }
+}
@@ -1,9 +1,29 @@
 #![no_std]
 
+mod banked_memory;
 mod bus;
 mod cpu;
+mod inspector;
+mod interrupt;
+mod memory_map;
+mod open_bus;
 mod pins;
+mod port;
+mod scheduler;
+mod unstable;
+mod variant;
 
+pub use banked_memory::{BankedMemory, Window};
 pub use bus::Bus;
-pub use cpu::{Cpu, Status};
-pub use pins::Pins;
+pub use cpu::{Cpu, Interrupt, RunState, Status, StepResult};
+pub use inspector::{Inspector, NoInspector};
+pub use interrupt::{InterruptLine, Source};
+pub use memory_map::{MemoryMap, RomWrites};
+pub use open_bus::OpenBus;
+pub use pins::{CycleKind, Pins};
+pub use scheduler::Scheduler;
+pub use unstable::{ShModel, Unstable};
+pub use variant::{
+    Cmos, InstructionSet, Mos6507, Mos6510, Nmos, RevisionA, Ricoh2A03,
+    Rockwell, Variant, Wdc,
+};
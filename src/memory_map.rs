@@ -0,0 +1,212 @@
+use core::ops::RangeInclusive;
+
+use crate::{Bus, Pins};
+
+const PAGE_SHIFT: u32 = 8;
+const PAGES: usize = 1 << (16 - PAGE_SHIFT);
+
+// Page table entries that aren't region indices.
+const UNMAPPED: u8 = 0xff;
+const SHARED: u8 = 0xfe;
+
+/// What a ROM does with writes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RomWrites {
+    /// Writes are ignored.
+    Ignore,
+    /// Writes are ignored, but the first one is kept for
+    /// [`MemoryMap::take_rom_write`].
+    Trap,
+}
+
+enum Backing<'a> {
+    Ram(&'a mut [u8]),
+    Rom(&'a [u8], RomWrites),
+    Device(&'a mut dyn Bus),
+}
+
+struct Region<'a> {
+    start: u16,
+    end: u16,
+    backing: Backing<'a>,
+}
+
+/// A [`Bus`] made up of RAM, ROM and devices mapped to address ranges.
+///
+/// RAM and ROM that are smaller than their range are mirrored across it.
+/// Devices are buses themselves and see the full address, so they handle
+/// their own mirroring. Regions mapped later take priority over the ones they
+/// overlap, and reads from unmapped addresses leave the data bus alone.
+pub struct MemoryMap<'a> {
+    regions: [Option<Region<'a>>; MemoryMap::MAX_REGIONS],
+    len: usize,
+    // The region that covers each page, or SHARED if more than one does.
+    pages: [u8; PAGES],
+    rom_write: Option<(u16, u8)>,
+}
+
+impl<'a> MemoryMap<'a> {
+    /// The number of regions a `MemoryMap` can hold.
+    pub const MAX_REGIONS: usize = 16;
+
+    /// Constructs a new `MemoryMap` with nothing mapped.
+    pub fn new() -> MemoryMap<'a> {
+        MemoryMap {
+            regions: [const { None }; MemoryMap::MAX_REGIONS],
+            len: 0,
+            pages: [UNMAPPED; PAGES],
+            rom_write: None,
+        }
+    }
+
+    /// Maps `ram` to `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ram` is empty, `range` is reversed or the map is full.
+    pub fn ram(&mut self, range: RangeInclusive<u16>, ram: &'a mut [u8]) {
+        assert!(!ram.is_empty(), "RAM is empty");
+        self.map(range, Backing::Ram(ram));
+    }
+
+    /// Maps `rom` to `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rom` is empty, `range` is reversed or the map is full.
+    pub fn rom(
+        &mut self,
+        range: RangeInclusive<u16>,
+        rom: &'a [u8],
+        writes: RomWrites,
+    ) {
+        assert!(!rom.is_empty(), "ROM is empty");
+        self.map(range, Backing::Rom(rom, writes));
+    }
+
+    /// Maps `device` to `range`. The device is ticked for every access in the
+    /// range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is reversed or the map is full.
+    pub fn device(
+        &mut self,
+        range: RangeInclusive<u16>,
+        device: &'a mut dyn Bus,
+    ) {
+        self.map(range, Backing::Device(device));
+    }
+
+    /// Returns the first write to a trapping ROM since the last call, as an
+    /// address and data.
+    pub fn take_rom_write(&mut self) -> Option<(u16, u8)> {
+        self.rom_write.take()
+    }
+
+    fn map(&mut self, range: RangeInclusive<u16>, backing: Backing<'a>) {
+        assert!(self.len < MemoryMap::MAX_REGIONS, "memory map is full");
+        assert!(range.start() <= range.end(), "memory range is reversed");
+
+        let (start, end) = range.into_inner();
+        let index = self.len;
+        self.regions[index] = Some(Region {
+            start,
+            end,
+            backing,
+        });
+        self.len += 1;
+
+        for page in (start >> PAGE_SHIFT)..=(end >> PAGE_SHIFT) {
+            let first = page << PAGE_SHIFT;
+            let last = first | 0xff;
+            self.pages[page as usize] = if start <= first && last <= end {
+                index as u8
+            } else {
+                SHARED
+            };
+        }
+    }
+
+    fn index(&self, address: u16) -> Option<usize> {
+        match self.pages[(address >> PAGE_SHIFT) as usize] {
+            UNMAPPED => None,
+            // The latest region that contains the address wins.
+            SHARED => self.regions[..self.len].iter().rposition(|region| {
+                region.as_ref().is_some_and(|region| {
+                    (region.start..=region.end).contains(&address)
+                })
+            }),
+            index => Some(index as usize),
+        }
+    }
+
+    fn find(&mut self, address: u16) -> Option<&mut Region<'a>> {
+        let index = self.index(address)?;
+        self.regions[index].as_mut()
+    }
+}
+
+impl Default for MemoryMap<'_> {
+    fn default() -> Self {
+        MemoryMap::new()
+    }
+}
+
+impl Bus for MemoryMap<'_> {
+    fn tick(&mut self, pins: &mut Pins) {
+        let Some(region) = self.find(pins.address) else {
+            return;
+        };
+
+        let offset = (pins.address - region.start) as usize;
+        match &mut region.backing {
+            Backing::Ram(ram) => {
+                let offset = offset % ram.len();
+                match pins.rw {
+                    true => pins.data = ram[offset],
+                    false => ram[offset] = pins.data,
+                }
+            }
+            Backing::Rom(rom, writes) => match pins.rw {
+                true => pins.data = rom[offset % rom.len()],
+                false => {
+                    if *writes == RomWrites::Trap && self.rom_write.is_none() {
+                        self.rom_write = Some((pins.address, pins.data));
+                    }
+                }
+            },
+            Backing::Device(device) => device.tick(pins),
+        }
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        let index = self.index(address)?;
+        let region = self.regions[index].as_ref()?;
+
+        let offset = (address - region.start) as usize;
+        match &region.backing {
+            Backing::Ram(ram) => Some(ram[offset % ram.len()]),
+            Backing::Rom(rom, _) => Some(rom[offset % rom.len()]),
+            Backing::Device(device) => device.peek(address),
+        }
+    }
+
+    fn poke(&mut self, address: u16, data: u8) -> bool {
+        let Some(region) = self.find(address) else {
+            return false;
+        };
+
+        let offset = (address - region.start) as usize;
+        match &mut region.backing {
+            Backing::Ram(ram) => {
+                let len = ram.len();
+                ram[offset % len] = data;
+                true
+            }
+            // ROM is borrowed immutably, so it can't be patched.
+            Backing::Rom(..) => false,
+            Backing::Device(device) => device.poke(address, data),
+        }
+    }
+}
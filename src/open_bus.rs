@@ -0,0 +1,88 @@
+use crate::Pins;
+
+/// The last value driven on the data bus, which reads that nothing answers
+/// return instead.
+///
+/// The value is held by the capacitance of the bus, so it can optionally
+/// decay, with each bit fading to zero a number of cycles after it was last
+/// driven.
+///
+/// An `OpenBus` doesn't see [`Cpu::cycles`](crate::Cpu::cycles), so it counts
+/// cycles itself. Buses have to call one of [`OpenBus::drive`],
+/// [`OpenBus::drive_bits`] or [`OpenBus::float`] for every cycle, and
+/// [`OpenBus::wait`] for any wait states they add, or the decay runs slow.
+#[derive(Clone, Copy, Debug)]
+pub struct OpenBus {
+    value: u8,
+    decay: Option<u64>,
+    cycles: u64,
+    // The cycle each bit was last driven during.
+    driven: [u64; 8],
+}
+
+impl OpenBus {
+    /// Constructs a new `OpenBus` that holds its value forever.
+    pub fn new() -> OpenBus {
+        OpenBus {
+            value: 0,
+            decay: None,
+            cycles: 0,
+            driven: [0; 8],
+        }
+    }
+
+    /// Constructs a new `OpenBus` whose bits fade to zero `cycles` cycles
+    /// after they were last driven.
+    pub fn with_decay(cycles: u64) -> OpenBus {
+        OpenBus {
+            decay: Some(cycles),
+            ..OpenBus::new()
+        }
+    }
+
+    /// Returns the value on the bus.
+    pub fn value(&self) -> u8 {
+        let Some(decay) = self.decay else {
+            return self.value;
+        };
+
+        (0..8)
+            .filter(|&bit| self.cycles - self.driven[bit] < decay)
+            .fold(0, |value, bit| value | (self.value & (1 << bit)))
+    }
+
+    /// Records a cycle during which something drove the data bus.
+    pub fn drive(&mut self, pins: &Pins) {
+        self.drive_bits(pins.data, 0xff);
+    }
+
+    /// Records a cycle during which something drove only the bits of the
+    /// data bus that are set in `mask`, e.g., a register with unused bits.
+    pub fn drive_bits(&mut self, data: u8, mask: u8) {
+        self.cycles += 1;
+        for bit in 0..8 {
+            if mask & (1 << bit) != 0 {
+                self.driven[bit] = self.cycles;
+            }
+        }
+        self.value = (self.value & !mask) | (data & mask);
+    }
+
+    /// Records a read that nothing answered, which returns the value on the
+    /// bus.
+    pub fn float(&mut self, pins: &mut Pins) {
+        self.cycles += 1;
+        pins.data = self.value();
+    }
+
+    /// Records `cycles` wait states, during which the bus holds its value.
+    pub fn wait(&mut self, cycles: u8) {
+        self.cycles += cycles as u64;
+    }
+}
+
+impl Default for OpenBus {
+    fn default() -> Self {
+        OpenBus::new()
+    }
+}
@@ -1,10 +1,35 @@
+use crate::InterruptLine;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Pins {
     pub address: u16,
     pub data: u8,
     pub rw: bool,
-    pub irq: bool,
-    pub nmi: bool,
+    pub irq: InterruptLine,
+    pub nmi: InterruptLine,
     pub rst: bool,
+    /// When low, the CPU repeats read cycles until it goes high again. Write
+    /// cycles aren't affected.
+    pub rdy: bool,
+    /// Asserting SO, i.e., pulling the pin low, sets the V flag. The 6507 and
+    /// 6510 don't have it.
+    pub so: bool,
+    /// Set during opcode fetches.
+    pub sync: bool,
+    /// Set during interrupt vector fetches. Only the W65C02S has it.
+    pub vp: bool,
+    /// Set during the read, modify and write cycles of read-modify-write
+    /// instructions. Only the W65C02S has it.
+    pub ml: bool,
+    /// The 6510 I/O port lines. The CPU drives the lines set as outputs and
+    /// pulls the rest high, which the bus can then pull low.
+    pub port: u8,
+    /// What the current bus cycle is for.
+    pub kind: CycleKind,
+    /// The number of wait states the bus adds to the current cycle, e.g., for
+    /// slow memory. The CPU clears it before each cycle and adds it to
+    /// [`Cpu::cycles`](crate::Cpu::cycles) after.
+    pub wait: u8,
 }
 
 impl Default for Pins {
@@ -13,9 +38,51 @@ impl Default for Pins {
             address: 0,
             data: 0,
             rw: true,
-            irq: false,
-            nmi: false,
+            irq: InterruptLine::default(),
+            nmi: InterruptLine::default(),
             rst: false,
+            rdy: true,
+            so: false,
+            sync: false,
+            vp: false,
+            ml: false,
+            port: 0xff,
+            kind: CycleKind::Read,
+            wait: 0,
         }
     }
 }
+
+/// What a bus cycle is for, which the chip doesn't show on its pins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CycleKind {
+    /// An opcode fetch, including the one an interrupt sequence throws away.
+    Opcode,
+    /// A read of an instruction operand.
+    Operand,
+    /// A read of data or of an indirect address.
+    Read,
+    /// A write of data.
+    Write,
+    /// A read whose data is thrown away, e.g., while indexing across a page.
+    DummyRead,
+    /// The write of the unmodified value by an NMOS read-modify-write
+    /// instruction.
+    DummyWrite,
+    /// A push to the stack.
+    Push,
+    /// A pull from the stack.
+    Pull,
+    /// A read of an interrupt vector.
+    Vector,
+}
+
+impl CycleKind {
+    /// Returns whether the cycle is a write.
+    pub fn is_write(self) -> bool {
+        matches!(
+            self,
+            CycleKind::Write | CycleKind::DummyWrite | CycleKind::Push
+        )
+    }
+}
@@ -0,0 +1,81 @@
+use crate::Variant;
+
+const DDR_ADDRESS: u16 = 0x0000;
+
+/// The on-chip I/O port of the 6510, made up of a data direction register at
+/// 0x0000 and a data register at 0x0001.
+#[derive(Clone, Copy)]
+pub(crate) struct IoPort {
+    ddr: u8,
+    data: u8,
+    // Floating bits that are switched to input keep their last output value
+    // as a charge for a while before fading to zero.
+    charge: u8,
+    fall_off: [u64; 8],
+}
+
+impl IoPort {
+    pub(crate) fn new() -> IoPort {
+        IoPort {
+            ddr: 0,
+            data: 0,
+            charge: 0,
+            fall_off: [0; 8],
+        }
+    }
+
+    /// Returns the levels of the port lines. Input lines are pulled high.
+    pub(crate) fn lines(&self) -> u8 {
+        (self.data & self.ddr) | !self.ddr
+    }
+
+    pub(crate) fn read<V: Variant>(
+        &mut self,
+        address: u16,
+        input: u8,
+        cycles: u64,
+    ) -> u8 {
+        if address == DDR_ADDRESS {
+            return self.ddr;
+        }
+
+        for bit in 0..8 {
+            if self.charge & (1 << bit) != 0 && cycles >= self.fall_off[bit] {
+                self.charge &= !(1 << bit);
+            }
+        }
+
+        let floating = !self.ddr & V::IO_PORT_FLOATING;
+        let input = !self.ddr & !V::IO_PORT_FLOATING & input;
+
+        (self.data & self.ddr) | (self.charge & floating) | input
+    }
+
+    pub(crate) fn write<V: Variant>(
+        &mut self,
+        address: u16,
+        data: u8,
+        cycles: u64,
+    ) {
+        // Floating bits are charged by whatever they last output.
+        let (charged, value) = if address == DDR_ADDRESS {
+            (self.ddr & !data, self.data)
+        } else {
+            (self.ddr, data)
+        };
+
+        for bit in 0..8 {
+            let mask = 1 << bit;
+            if charged & V::IO_PORT_FLOATING & mask != 0 {
+                self.charge = (self.charge & !mask) | (value & mask);
+                self.fall_off[bit] = cycles + V::IO_PORT_FALL_OFF;
+            }
+        }
+
+        if address == DDR_ADDRESS {
+            self.ddr = data;
+        } else {
+            self.data = data;
+        }
+    }
+}
@@ -0,0 +1,110 @@
+/// A queue of up to `N` events, each due at a cycle count.
+///
+/// Devices schedule events for when something happens, e.g., a timer
+/// underflowing, instead of counting every cycle. The host runs the CPU to the
+/// exact cycle of the next event with
+/// [`Cpu::run_until_cycle`](crate::Cpu::run_until_cycle) and then handles the
+/// events that are due, which can assert interrupt lines or change other pins.
+/// Events come out in the order they're due, and events due on the same cycle
+/// come out in the order they were scheduled.
+///
+/// The events are stored inline, so the scheduler doesn't grow: scheduling an
+/// event when `N` are already queued panics.
+pub struct Scheduler<E, const N: usize> {
+    events: [Option<(u64, u64, E)>; N],
+    len: usize,
+    // Breaks ties between events due on the same cycle.
+    sequence: u64,
+}
+
+impl<E, const N: usize> Scheduler<E, N> {
+    /// Constructs a new, empty `Scheduler`.
+    pub fn new() -> Scheduler<E, N> {
+        Scheduler {
+            events: [const { None }; N],
+            len: 0,
+            sequence: 0,
+        }
+    }
+
+    /// Schedules `event` for `cycle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scheduler is full.
+    pub fn schedule(&mut self, cycle: u64, event: E) {
+        let slot = self
+            .events
+            .iter_mut()
+            .find(|slot| slot.is_none())
+            .expect("scheduler is full");
+        *slot = Some((cycle, self.sequence, event));
+        self.sequence += 1;
+        self.len += 1;
+    }
+
+    /// Schedules `event` for `cycles` cycles after `now`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scheduler is full.
+    pub fn schedule_in(&mut self, now: u64, cycles: u64, event: E) {
+        self.schedule(now + cycles, event);
+    }
+
+    /// Removes the events that `f` returns true for.
+    pub fn cancel<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&E) -> bool,
+    {
+        for slot in self.events.iter_mut() {
+            if slot.as_ref().is_some_and(|(_, _, event)| f(event)) {
+                *slot = None;
+                self.len -= 1;
+            }
+        }
+    }
+
+    /// Returns the cycle the next event is due, if there is one.
+    pub fn next_cycle(&self) -> Option<u64> {
+        self.next().map(|(_, cycle)| cycle)
+    }
+
+    /// Removes and returns the next event if it's due by `now`, along with
+    /// the cycle it was due.
+    pub fn pop(&mut self, now: u64) -> Option<(u64, E)> {
+        let (index, cycle) = self.next().filter(|&(_, cycle)| cycle <= now)?;
+        let (_, _, event) = self.events[index].take()?;
+        self.len -= 1;
+        Some((cycle, event))
+    }
+
+    /// Returns the number of scheduled events.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether no events are scheduled.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Returns the index and cycle of the next event.
+    fn next(&self) -> Option<(usize, u64)> {
+        self.events
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                slot.as_ref()
+                    .map(|&(cycle, sequence, _)| (index, cycle, sequence))
+            })
+            .min_by_key(|&(_, cycle, sequence)| (cycle, sequence))
+            .map(|(index, cycle, _)| (index, cycle))
+    }
+}
+
+impl<E, const N: usize> Default for Scheduler<E, N> {
+    fn default() -> Self {
+        Scheduler::new()
+    }
+}
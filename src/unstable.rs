@@ -0,0 +1,53 @@
+/// How the unstable illegal opcodes behave.
+///
+/// These opcodes depend on analog effects that vary between chips, and even
+/// with temperature, so there's no single right answer. The presets match
+/// what the test suites for each platform expect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Unstable {
+    /// The constant that ANE ORs with the accumulator, or `None` if ANE
+    /// only reads its operand, like a NOP.
+    pub ane_magic: Option<u8>,
+    /// The constant that LXA ORs with the accumulator.
+    pub lxa_magic: u8,
+    /// How SHA, SHX, SHY and TAS compute the address and value they store.
+    pub sh: ShModel,
+}
+
+impl Unstable {
+    /// The behavior of Visual6502.
+    pub const VISUAL_6502: Unstable = Unstable {
+        ane_magic: Some(0xee),
+        lxa_magic: 0xee,
+        sh: ShModel::PageCross,
+    };
+
+    /// The behavior of most C64 6510s, which Lorenz's test suite expects.
+    pub const C64: Unstable = Unstable {
+        ane_magic: Some(0xef),
+        lxa_magic: 0xee,
+        sh: ShModel::PageCross,
+    };
+
+    /// The behavior that blargg's NES test ROMs expect from the 2A03. ANE is
+    /// too unstable for NES games to rely on, so it's left as a NOP.
+    pub const NES: Unstable = Unstable {
+        ane_magic: None,
+        lxa_magic: 0xff,
+        sh: ShModel::Always,
+    };
+}
+
+/// The models for SHA, SHX, SHY and TAS. They all store a register, or A AND
+/// X, ANDed with the high byte of an address plus one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShModel {
+    /// The AND uses the high byte of the base address. If indexing crosses a
+    /// page, the stored value also replaces the high byte of the target
+    /// address.
+    PageCross,
+    /// The AND uses the high byte of the target address, and the result
+    /// always replaces it. This is from
+    /// <https://forums.nesdev.org/viewtopic.php?f=3&t=3831&start=30>.
+    Always,
+}
@@ -0,0 +1,158 @@
+use crate::Unstable;
+
+/// The instruction sets that a [`Variant`] can decode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InstructionSet {
+    /// The NMOS 6502 instruction set, including the illegal opcodes.
+    Nmos,
+    /// The CMOS 65C02 instruction set, where the illegal opcodes are NOPs.
+    Cmos,
+    /// The CMOS instruction set plus the Rockwell bit instructions, i.e.,
+    /// RMB, SMB, BBR and BBS.
+    Rockwell,
+    /// The Rockwell instruction set plus WAI and STP.
+    Wdc,
+}
+
+/// The differences between members of the 6502 family.
+///
+/// The defaults describe an NMOS 6502, so a variant only needs to override
+/// what's different about its chip.
+pub trait Variant {
+    /// The opcode table used to decode instructions.
+    const INSTRUCTION_SET: InstructionSet = InstructionSet::Nmos;
+
+    /// Whether the D flag switches ADC and SBC to BCD arithmetic.
+    const DECIMAL_MODE: bool = true;
+
+    /// Whether `JMP (abs)` fetches the high byte of the target from the
+    /// start of the page when the pointer is at the end of a page.
+    const JMP_INDIRECT_BUG: bool = true;
+
+    /// Whether ROR is implemented. Chips without it shift left instead,
+    /// shifting in a zero and leaving the carry alone.
+    const ROR: bool = true;
+
+    /// The default behavior of the unstable illegal opcodes. It can be
+    /// changed at runtime with [`Cpu::unstable`](crate::Cpu::unstable).
+    ///
+    /// The NES preset is the default because ANE, LXA and SHA, SHX, SHY and
+    /// TAS have always behaved like it. The other presets are opt-in.
+    const UNSTABLE: Unstable = Unstable::NES;
+
+    /// Whether an NMI detected during a BRK or IRQ sequence takes over its
+    /// vector fetch.
+    const INTERRUPT_HIJACKING: bool = true;
+
+    /// Whether taking an interrupt, including reset, clears the D flag.
+    const INTERRUPTS_CLEAR_DECIMAL: bool = false;
+
+    /// Whether there's a 6510 I/O port at 0x0000 and 0x0001.
+    const IO_PORT: bool = false;
+
+    /// The I/O port bits that aren't connected to anything. They read back
+    /// their last output for a while after being switched to input.
+    const IO_PORT_FLOATING: u8 = 0;
+
+    /// The number of cycles it takes for a floating I/O port bit to fade.
+    const IO_PORT_FALL_OFF: u64 = 0;
+
+    /// The address lines that are bonded out. Addresses are masked with this
+    /// before they're put on the bus.
+    const ADDRESS_MASK: u16 = 0xffff;
+
+    /// Whether the IRQ and NMI pins are bonded out. Without them, the CPU
+    /// ignores `pins.irq` and `pins.nmi`.
+    const INTERRUPT_PINS: bool = true;
+
+    /// Whether the SO pin is bonded out. Without it, the CPU ignores
+    /// `pins.so`.
+    const SO_PIN: bool = true;
+
+    /// Whether there are VP and ML pins, which only the W65C02S has. Without
+    /// them, the CPU leaves `pins.vp` and `pins.ml` low.
+    const VP_AND_ML_PINS: bool = false;
+}
+
+/// The NMOS 6502.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Nmos;
+
+impl Variant for Nmos {}
+
+/// The Ricoh 2A03 used in the NES. It doesn't have decimal mode.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ricoh2A03;
+
+impl Variant for Ricoh2A03 {
+    const DECIMAL_MODE: bool = false;
+}
+
+/// The MOS 6510 used in the Commodore 64. Bits 6 and 7 of its I/O port
+/// aren't connected.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Mos6510;
+
+impl Variant for Mos6510 {
+    const UNSTABLE: Unstable = Unstable::C64;
+    const IO_PORT: bool = true;
+    const IO_PORT_FLOATING: u8 = 0xc0;
+    const IO_PORT_FALL_OFF: u64 = 350_000;
+    const SO_PIN: bool = false;
+}
+
+/// The MOS 6507 used in the Atari 2600. It only has 13 address lines and no
+/// IRQ, NMI or SO pins.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Mos6507;
+
+impl Variant for Mos6507 {
+    const ADDRESS_MASK: u16 = 0x1fff;
+    const INTERRUPT_PINS: bool = false;
+    const SO_PIN: bool = false;
+}
+
+/// An early NMOS 6502, made before June 1976, without a working ROR.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RevisionA;
+
+impl Variant for RevisionA {
+    const ROR: bool = false;
+}
+
+/// A CMOS 65C02 without the Rockwell and WDC extensions, like the one in the
+/// enhanced Apple IIe.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cmos;
+
+impl Variant for Cmos {
+    const INSTRUCTION_SET: InstructionSet = InstructionSet::Cmos;
+    const JMP_INDIRECT_BUG: bool = false;
+    const INTERRUPT_HIJACKING: bool = false;
+    const INTERRUPTS_CLEAR_DECIMAL: bool = true;
+}
+
+/// A Rockwell R65C02, which adds the bit manipulation and branch
+/// instructions to the 65C02.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Rockwell;
+
+impl Variant for Rockwell {
+    const INSTRUCTION_SET: InstructionSet = InstructionSet::Rockwell;
+    const JMP_INDIRECT_BUG: bool = false;
+    const INTERRUPT_HIJACKING: bool = false;
+    const INTERRUPTS_CLEAR_DECIMAL: bool = true;
+}
+
+/// A WDC W65C02S, which adds WAI and STP to the Rockwell instructions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Wdc;
+
+impl Variant for Wdc {
+    const INSTRUCTION_SET: InstructionSet = InstructionSet::Wdc;
+    const JMP_INDIRECT_BUG: bool = false;
+    const INTERRUPT_HIJACKING: bool = false;
+    const INTERRUPTS_CLEAR_DECIMAL: bool = true;
+    const VP_AND_ML_PINS: bool = true;
+}
@@ -0,0 +1,145 @@
+use bog::{BankedMemory, Bus, Cpu, Pins, Window};
+
+const PRG_BANK_SIZE: usize = 0x4000;
+
+// A UxROM cartridge, which switches the 16 KB PRG bank at 0x8000 when ROM is
+// written and fixes the last bank at 0xc000.
+struct Uxrom<'a> {
+    prg: BankedMemory<'a, 2>,
+}
+
+impl<'a> Uxrom<'a> {
+    fn new(prg_rom: &'a mut [u8]) -> Uxrom<'a> {
+        let last_bank = prg_rom.len() / PRG_BANK_SIZE - 1;
+        let mut prg =
+            BankedMemory::new(prg_rom, [0x8000..=0xbfff, 0xc000..=0xffff]);
+        // Only reads are mapped, since the ROM can't be written.
+        prg.map_read(0, Some(0));
+        prg.map_read(1, Some(last_bank * PRG_BANK_SIZE));
+
+        Uxrom { prg }
+    }
+}
+
+impl Bus for Uxrom<'_> {
+    fn tick(&mut self, pins: &mut Pins) {
+        if !pins.rw && pins.address >= 0x8000 {
+            let bank = (pins.data & 0x07) as usize;
+            self.prg.map_read(0, Some(bank * PRG_BANK_SIZE));
+        }
+        self.prg.tick(pins);
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        self.prg.peek(address)
+    }
+}
+
+#[test]
+fn uxrom_switches_banks() {
+    let mut prg_rom = vec![0; 4 * PRG_BANK_SIZE];
+    for (bank, chunk) in prg_rom.chunks_mut(PRG_BANK_SIZE).enumerate() {
+        chunk.fill(bank as u8);
+    }
+    // LDA #$02; STA $C000; LDX $8000; JMP $C008
+    let program = [
+        0xa9, 0x02, 0x8d, 0x00, 0xc0, 0xae, 0x00, 0x80, 0x4c, 0x08, 0xc0,
+    ];
+    let fixed = 3 * PRG_BANK_SIZE;
+    prg_rom[fixed..fixed + program.len()].copy_from_slice(&program);
+    prg_rom[fixed + 0x3ffc] = 0x00;
+    prg_rom[fixed + 0x3ffd] = 0xc0;
+
+    let mut cpu = Cpu::new(Uxrom::new(&mut prg_rom));
+    assert_eq!(cpu.peek_memory(0x8000), Some(0x00));
+
+    cpu.run_until_trap();
+    assert_eq!(cpu.x, 0x02);
+    assert_eq!(cpu.peek_memory(0x8000), Some(0x02));
+    assert_eq!(
+        cpu.bus.prg.window(0),
+        Window {
+            start: 0x8000,
+            end: 0xbfff,
+            read: Some(2 * PRG_BANK_SIZE),
+            write: None,
+        }
+    );
+
+    // The ROM itself wasn't written.
+    assert_eq!(cpu.bus.prg.read_offset(0xc000), Some(fixed));
+    assert_eq!(cpu.peek_memory(0xc000), Some(0xa9));
+}
+
+#[test]
+fn overlay_reads_rom_and_writes_ram() {
+    // 64 KB of RAM followed by an 8 KB ROM.
+    let mut memory = vec![0; 0x12000];
+    memory[0x10000..].fill(0xea);
+
+    let mut banks =
+        BankedMemory::new(&mut memory, [0x0000..=0xffff, 0xa000..=0xbfff]);
+    banks.select(0, 0);
+    banks.map_read(1, Some(0x10000));
+
+    // Writes fall through to the RAM under the ROM.
+    assert!(banks.poke(0xa000, 0x42));
+    assert_eq!(banks.memory()[0xa000], 0x42);
+    assert_eq!(banks.peek(0xa000), Some(0xea));
+    assert_eq!(banks.write_offset(0xa000), Some(0xa000));
+
+    // Switching the ROM out shows the RAM.
+    banks.map_read(1, None);
+    assert_eq!(banks.peek(0xa000), Some(0x42));
+    assert_eq!(banks.read_offset(0xa000), Some(0xa000));
+}
+
+#[test]
+fn unmapped_windows_leave_the_data_bus_alone() {
+    let mut memory = [0x11; 0x100];
+    let mut banks = BankedMemory::new(&mut memory, [0x0000..=0x00ff]);
+
+    let mut pins = Pins {
+        address: 0x0010,
+        data: 0x5a,
+        ..Pins::default()
+    };
+    banks.tick(&mut pins);
+    assert_eq!(pins.data, 0x5a);
+    assert_eq!(banks.peek(0x0010), None);
+    assert!(!banks.poke(0x0010, 0x00));
+
+    banks.map(0, 0x80);
+    banks.tick(&mut pins);
+    assert_eq!(pins.data, 0x11);
+    assert_eq!(banks.read_offset(0x00ff), Some(0x7f));
+}
+
+#[test]
+#[should_panic(expected = "window range is reversed")]
+fn reversed_windows_are_rejected() {
+    let mut memory = [0; 0x100];
+    let (start, end) = (0x00ff, 0x0000);
+    BankedMemory::new(&mut memory, [start..=end]);
+}
+
+#[test]
+fn large_offsets_wrap_around() {
+    let mut memory = [0; 0x100];
+    memory[0xff] = 0x42;
+    let mut banks = BankedMemory::new(&mut memory, [0x0000..=0x00ff]);
+
+    banks.map(0, usize::MAX);
+    assert_eq!(banks.window(0).read, Some(usize::MAX % 0x100));
+    assert_eq!(banks.read_offset(0x0000), Some(0xff));
+    assert_eq!(banks.read_offset(0x0001), Some(0x00));
+    assert_eq!(banks.peek(0x0000), Some(0x42));
+}
+
+#[test]
+#[should_panic(expected = "bank offset overflows")]
+fn bank_offsets_are_checked() {
+    let mut memory = [0; 0x100];
+    let mut banks = BankedMemory::new(&mut memory, [0x0000..=0x00ff]);
+    banks.select(0, usize::MAX);
+}
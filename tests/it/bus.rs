@@ -0,0 +1,103 @@
+use bog::{Bus, Cpu, Mos6510, Pins};
+
+use crate::common::{TestBus, PROGRAM_START};
+const STATUS_REGISTER: u16 = 0x4000;
+
+// The shared bus, plus a status register that clears when it's read.
+struct BusTestBus {
+    ram: TestBus,
+    status: u8,
+}
+
+impl Bus for BusTestBus {
+    fn tick(&mut self, pins: &mut Pins) {
+        match (pins.rw, pins.address) {
+            (true, STATUS_REGISTER) => {
+                pins.data = self.status;
+                self.status = 0;
+            }
+            _ => self.ram.tick(pins),
+        }
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        match address {
+            STATUS_REGISTER => Some(self.status),
+            _ => self.ram.peek(address),
+        }
+    }
+
+    fn poke(&mut self, address: u16, data: u8) -> bool {
+        match address {
+            STATUS_REGISTER => false,
+            _ => self.ram.poke(address, data),
+        }
+    }
+}
+
+// A bus that only implements `tick`.
+struct TickOnlyBus;
+
+impl Bus for TickOnlyBus {
+    fn tick(&mut self, pins: &mut Pins) {
+        pins.data = 0xea;
+    }
+}
+
+fn new_bus(program: &[u8]) -> BusTestBus {
+    BusTestBus {
+        ram: TestBus::new(program),
+        status: 0x80,
+    }
+}
+
+#[test]
+fn peek_has_no_side_effects() {
+    // LDA $4000
+    let mut cpu = Cpu::new(new_bus(&[0xad, 0x00, 0x40]));
+    cpu.step();
+
+    assert_eq!(cpu.peek_memory(PROGRAM_START), Some(0xad));
+    assert_eq!(cpu.peek_memory(STATUS_REGISTER), Some(0x80));
+    assert_eq!(cpu.peek_memory(STATUS_REGISTER), Some(0x80));
+
+    cpu.step();
+    assert_eq!(cpu.a, 0x80);
+    assert_eq!(cpu.peek_memory(STATUS_REGISTER), Some(0x00));
+}
+
+#[test]
+fn poke() {
+    // LDA $10
+    let mut cpu = Cpu::new(new_bus(&[0xa5, 0x10]));
+    cpu.step();
+
+    assert!(cpu.bus.poke(0x0010, 0x42));
+    assert!(!cpu.bus.poke(STATUS_REGISTER, 0x00));
+    assert_eq!(cpu.peek_memory(STATUS_REGISTER), Some(0x80));
+
+    cpu.step();
+    assert_eq!(cpu.a, 0x42);
+}
+
+#[test]
+fn peek_and_poke_default_to_unsupported() {
+    let mut bus = TickOnlyBus;
+    assert!(!bus.poke(0x0000, 0x00));
+
+    let cpu = Cpu::new(bus);
+    assert_eq!(cpu.peek_memory(0x0000), None);
+}
+
+#[test]
+fn mos6510_peeks_io_port() {
+    // LDA #$2f; STA $00; LDA #$35; STA $01
+    let program = [0xa9, 0x2f, 0x85, 0x00, 0xa9, 0x35, 0x85, 0x01];
+    let mut cpu = Cpu::with_variant(new_bus(&program), Mos6510);
+    for _ in 0..5 {
+        cpu.step();
+    }
+
+    assert_eq!(cpu.peek_memory(0x0000), Some(0x2f));
+    assert_eq!(cpu.peek_memory(0x0001), Some(0x35));
+}
@@ -0,0 +1,103 @@
+use bog::{Bus, Cpu, Pins, Source, Variant};
+
+pub const PROGRAM_START: u16 = 0x0200;
+pub const NMI_HANDLER: u16 = 0x0300;
+pub const IRQ_HANDLER: u16 = 0x0400;
+// The source that `TestBus` asserts IRQ and NMI with.
+pub const DEVICE: Source = Source::new(0);
+
+// 64K of RAM with the program at `PROGRAM_START` and the vectors pointing at
+// it and the handlers. It records every access, and can assert IRQ and NMI
+// from a given tick on.
+pub struct TestBus {
+    pub memory: [u8; 0x10000],
+    pub accesses: Vec<(u16, u8, bool)>,
+    // The ticks, counting from one, from which IRQ and NMI are asserted.
+    // `None` leaves the line alone.
+    pub irq_at: Option<usize>,
+    pub nmi_at: Option<usize>,
+}
+
+impl TestBus {
+    pub fn new(program: &[u8]) -> TestBus {
+        TestBus::with_memory([0; 0x10000], program)
+    }
+
+    // Puts the program and the vectors on top of `memory`.
+    pub fn with_memory(mut memory: [u8; 0x10000], program: &[u8]) -> TestBus {
+        let start = PROGRAM_START as usize;
+        memory[start..start + program.len()].copy_from_slice(program);
+        memory[0xfffa..].copy_from_slice(&[
+            NMI_HANDLER as u8,
+            (NMI_HANDLER >> 8) as u8,
+            PROGRAM_START as u8,
+            (PROGRAM_START >> 8) as u8,
+            IRQ_HANDLER as u8,
+            (IRQ_HANDLER >> 8) as u8,
+        ]);
+
+        TestBus {
+            memory,
+            accesses: Vec::new(),
+            irq_at: None,
+            nmi_at: None,
+        }
+    }
+
+    pub fn ticks(&self) -> usize {
+        self.accesses.len()
+    }
+
+    // Asserts the IRQ or NMI line starting at the given tick, counting from
+    // the tick after this is called.
+    pub fn schedule(&mut self, irq_at: Option<usize>, nmi_at: Option<usize>) {
+        self.accesses.clear();
+        self.irq_at = irq_at;
+        self.nmi_at = nmi_at;
+    }
+}
+
+impl Bus for TestBus {
+    fn tick(&mut self, pins: &mut Pins) {
+        match pins.rw {
+            true => pins.data = self.memory[pins.address as usize],
+            false => self.memory[pins.address as usize] = pins.data,
+        }
+        self.accesses.push((pins.address, pins.data, pins.rw));
+
+        let ticks = self.ticks();
+        if let Some(tick) = self.irq_at {
+            pins.irq.set(DEVICE, ticks >= tick);
+        }
+        if let Some(tick) = self.nmi_at {
+            pins.nmi.set(DEVICE, ticks >= tick);
+        }
+    }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        Some(self.memory[address as usize])
+    }
+
+    fn poke(&mut self, address: u16, data: u8) -> bool {
+        self.memory[address as usize] = data;
+        true
+    }
+}
+
+// Constructs a CPU that has run through the reset sequence, so the program
+// starts with the next step.
+pub fn new_cpu<V: Variant>(program: &[u8], variant: V) -> Cpu<TestBus, V> {
+    with_bus(TestBus::new(program), variant)
+}
+
+pub fn with_bus<V: Variant>(bus: TestBus, variant: V) -> Cpu<TestBus, V> {
+    let mut cpu = Cpu::with_variant(bus, variant);
+
+    // Run through the reset sequence. Variants with fewer address lines read
+    // the vector from elsewhere.
+    cpu.step();
+    cpu.pc = PROGRAM_START;
+    cpu.bus.accesses.clear();
+
+    cpu
+}
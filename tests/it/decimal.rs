@@ -0,0 +1,156 @@
+use bog::{Cmos, Nmos, Status, Variant};
+
+use crate::common::{new_cpu, PROGRAM_START};
+
+const ADC_IMMEDIATE: u8 = 0x69;
+const SBC_IMMEDIATE: u8 = 0xe9;
+
+struct Expected {
+    a: u8,
+    n: bool,
+    v: bool,
+    z: bool,
+    c: bool,
+}
+
+fn predict_adc(a: u8, b: u8, c: bool) -> Expected {
+    let (a, b, c) = (a as i16, b as i16, c as i16);
+
+    // Sequence 1 determines the accumulator and carry.
+    let mut al = (a & 0x0f) + (b & 0x0f) + c;
+    if al >= 0x0a {
+        al = ((al + 0x06) & 0x0f) + 0x10;
+    }
+    let mut sum = (a & 0xf0) + (b & 0xf0) + al;
+    if sum >= 0xa0 {
+        sum += 0x60;
+    }
+
+    // Sequence 2 determines the N and V flags.
+    let mut al = (a & 0x0f) + (b & 0x0f) + c;
+    if al >= 0x0a {
+        al = ((al + 0x06) & 0x0f) + 0x10;
+    }
+    let signed =
+        (a & 0xf0) as u8 as i8 as i16 + (b & 0xf0) as u8 as i8 as i16 + al;
+
+    Expected {
+        a: sum as u8,
+        n: signed & 0x80 != 0,
+        v: !(-128..=127).contains(&signed),
+        z: (a + b + c) as u8 == 0,
+        c: sum >= 0x100,
+    }
+}
+
+fn predict_sbc(a: u8, b: u8, c: bool) -> Expected {
+    let (a, b, c) = (a as i16, b as i16, c as i16);
+
+    let mut al = (a & 0x0f) - (b & 0x0f) + c - 1;
+    if al < 0 {
+        al = ((al - 0x06) & 0x0f) - 0x10;
+    }
+    let mut difference = (a & 0xf0) - (b & 0xf0) + al;
+    if difference < 0 {
+        difference -= 0x60;
+    }
+
+    // The flags are the same as in binary mode.
+    let binary = a - b + c - 1;
+    let signed = a as u8 as i8 as i16 - b as u8 as i8 as i16 + c - 1;
+
+    Expected {
+        a: difference as u8,
+        n: binary & 0x80 != 0,
+        v: !(-128..=127).contains(&signed),
+        z: binary as u8 == 0,
+        c: binary >= 0,
+    }
+}
+
+// The 65C02 sets the N and Z flags from the result, and computes its SBC
+// result in one step instead of a nibble at a time.
+fn predict_cmos_adc(a: u8, b: u8, c: bool) -> Expected {
+    let expected = predict_adc(a, b, c);
+
+    Expected {
+        n: expected.a & 0x80 != 0,
+        z: expected.a == 0,
+        ..expected
+    }
+}
+
+fn predict_cmos_sbc(a: u8, b: u8, c: bool) -> Expected {
+    let expected = predict_sbc(a, b, c);
+    let (a, b, c) = (a as i16, b as i16, c as i16);
+
+    let al = (a & 0x0f) - (b & 0x0f) + c - 1;
+    let mut difference = a - b + c - 1;
+    if difference < 0 {
+        difference -= 0x60;
+    }
+    if al < 0 {
+        difference -= 0x06;
+    }
+    let difference = difference as u8;
+
+    Expected {
+        a: difference,
+        n: difference & 0x80 != 0,
+        z: difference == 0,
+        ..expected
+    }
+}
+
+// This is a port of Bruce Clark's exhaustive decimal mode test. Instead of
+// running the test program, we compare the CPU against the predictions from
+// his "Decimal Mode" tutorial for every operand and carry combination.
+fn run<V: Variant>(
+    variant: V,
+    opcode: u8,
+    predict: fn(u8, u8, bool) -> Expected,
+) {
+    let mut cpu = new_cpu(&[opcode], variant);
+    for a in 0..=0xff {
+        for b in 0..=0xff {
+            for c in [false, true] {
+                cpu.pc = PROGRAM_START;
+                cpu.a = a;
+                cpu.p = Status::default() | Status::D;
+                cpu.p.set(Status::C, c);
+                cpu.bus.memory[PROGRAM_START as usize + 1] = b;
+
+                cpu.step();
+
+                let expected = predict(a, b, c);
+                let context =
+                    format!("A = {a:02X}, operand = {b:02X}, C = {c}");
+                assert_eq!(cpu.a, expected.a, "{context}");
+                assert_eq!(cpu.p.contains(Status::N), expected.n, "{context}");
+                assert_eq!(cpu.p.contains(Status::V), expected.v, "{context}");
+                assert_eq!(cpu.p.contains(Status::Z), expected.z, "{context}");
+                assert_eq!(cpu.p.contains(Status::C), expected.c, "{context}");
+            }
+        }
+    }
+}
+
+#[test]
+fn adc() {
+    run(Nmos, ADC_IMMEDIATE, predict_adc);
+}
+
+#[test]
+fn sbc() {
+    run(Nmos, SBC_IMMEDIATE, predict_sbc);
+}
+
+#[test]
+fn cmos_adc() {
+    run(Cmos, ADC_IMMEDIATE, predict_cmos_adc);
+}
+
+#[test]
+fn cmos_sbc() {
+    run(Cmos, SBC_IMMEDIATE, predict_cmos_sbc);
+}
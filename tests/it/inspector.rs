@@ -0,0 +1,108 @@
+use bog::{Cpu, Inspector, Interrupt, Nmos, Pins, Status};
+
+use crate::common::TestBus;
+
+#[derive(Default)]
+struct Recorder {
+    instructions: Vec<(u16, u8)>,
+    accesses: Vec<(u16, u8, bool)>,
+    interrupts: Vec<(Interrupt, u16)>,
+}
+
+impl Inspector for Recorder {
+    fn instruction(&mut self, pc: u16, opcode: u8) {
+        self.instructions.push((pc, opcode));
+    }
+
+    fn access(&mut self, pins: &Pins) {
+        self.accesses.push((pins.address, pins.data, pins.rw));
+    }
+
+    fn interrupt(&mut self, interrupt: Interrupt, vector: u16) {
+        self.interrupts.push((interrupt, vector));
+    }
+}
+
+fn new_cpu(program: &[u8]) -> Cpu<TestBus, Nmos, Recorder> {
+    Cpu::with_inspector(TestBus::new(program), Nmos, Recorder::default())
+}
+
+#[test]
+fn inspector_sees_instructions_and_accesses() {
+    // LDA #$01; STA $10
+    let mut cpu = new_cpu(&[0xa9, 0x01, 0x85, 0x10]);
+    cpu.step();
+    cpu.step();
+    cpu.step();
+
+    let recorder = &cpu.inspector;
+    assert_eq!(recorder.interrupts, [(Interrupt::Reset, 0xfffc)]);
+    assert_eq!(recorder.instructions, [(0x0200, 0xa9), (0x0202, 0x85)]);
+    assert_eq!(
+        recorder.accesses[7..],
+        [
+            (0x0200, 0xa9, true),
+            (0x0201, 0x01, true),
+            (0x0202, 0x85, true),
+            (0x0203, 0x10, true),
+            (0x0010, 0x01, false),
+        ]
+    );
+}
+
+#[test]
+fn inspector_ignores_tick_replays() {
+    // LDA #$01; STA $10
+    let mut cpu = new_cpu(&[0xa9, 0x01, 0x85, 0x10]);
+    for _ in 0..12 {
+        cpu.tick();
+    }
+
+    let recorder = &cpu.inspector;
+    assert_eq!(recorder.interrupts, [(Interrupt::Reset, 0xfffc)]);
+    assert_eq!(recorder.instructions, [(0x0200, 0xa9), (0x0202, 0x85)]);
+    assert_eq!(recorder.accesses.len(), 12);
+    assert_eq!(recorder.accesses[11], (0x0010, 0x01, false));
+}
+
+#[test]
+fn inspector_sees_hijacked_brk() {
+    // BRK
+    let mut cpu = new_cpu(&[0x00]);
+    cpu.step();
+    cpu.step();
+
+    // A BRK on its own isn't an interrupt sequence.
+    assert_eq!(cpu.inspector.interrupts, [(Interrupt::Reset, 0xfffc)]);
+
+    // An NMI during the BRK's pushes hijacks it.
+    let mut cpu = new_cpu(&[0x00]);
+    cpu.step();
+    cpu.bus.nmi_at = Some(cpu.bus.ticks() + 3);
+    cpu.step();
+
+    assert_eq!(
+        cpu.inspector.interrupts,
+        [(Interrupt::Reset, 0xfffc), (Interrupt::Nmi, 0xfffa)]
+    );
+}
+
+#[test]
+fn inspector_sees_hijacked_irq() {
+    // NOP
+    let mut cpu = new_cpu(&[0xea]);
+    cpu.step();
+    cpu.p.remove(Status::I);
+
+    // The IRQ is taken after the NOP, and an NMI during its pushes hijacks
+    // it.
+    cpu.bus.irq_at = Some(cpu.bus.ticks() + 1);
+    cpu.bus.nmi_at = Some(cpu.bus.ticks() + 5);
+    cpu.step();
+    cpu.step();
+
+    assert_eq!(
+        cpu.inspector.interrupts,
+        [(Interrupt::Reset, 0xfffc), (Interrupt::Nmi, 0xfffa)]
+    );
+}
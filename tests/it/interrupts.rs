@@ -0,0 +1,288 @@
+use bog::{Cpu, InterruptLine, Nmos, RunState, Source, Status, Wdc};
+
+use crate::common::{self, TestBus, IRQ_HANDLER, NMI_HANDLER, PROGRAM_START};
+
+const BEQ: u8 = 0xf0;
+const BRK: u8 = 0x00;
+const JAM: u8 = 0x02;
+const NOP: u8 = 0xea;
+const SEI: u8 = 0x78;
+const STP: u8 = 0xdb;
+const WAI: u8 = 0xcb;
+
+fn new_cpu(program: &[u8]) -> Cpu<TestBus> {
+    let mut cpu = common::new_cpu(program, Nmos);
+    cpu.p.remove(Status::I);
+
+    cpu
+}
+
+fn pushed_status(cpu: &Cpu<TestBus>) -> Status {
+    let address = 0x0100 + cpu.s.wrapping_add(1) as usize;
+    Status::from_bits_truncate(cpu.bus.memory[address])
+}
+
+#[test]
+fn nmi_hijacks_brk() {
+    for tick in 1..=7 {
+        let mut cpu = new_cpu(&[BRK]);
+        cpu.bus.schedule(None, Some(tick));
+
+        cpu.step();
+
+        // An NMI during the first four cycles takes over the vector fetch,
+        // otherwise it runs after the BRK.
+        let expected = if tick <= 4 { NMI_HANDLER } else { IRQ_HANDLER };
+        assert_eq!(cpu.pc, expected, "NMI at cycle {tick}");
+        assert!(pushed_status(&cpu).contains(Status::B));
+    }
+}
+
+#[test]
+fn nmi_hijacks_irq() {
+    for tick in 1..=7 {
+        let mut cpu = new_cpu(&[NOP, NOP]);
+
+        // Let the IRQ be recognized during the first NOP.
+        cpu.bus.schedule(Some(1), None);
+        cpu.step();
+        cpu.bus.schedule(None, Some(tick));
+
+        cpu.step();
+
+        let expected = if tick <= 4 { NMI_HANDLER } else { IRQ_HANDLER };
+        assert_eq!(cpu.pc, expected, "NMI at cycle {tick}");
+        assert!(!pushed_status(&cpu).contains(Status::B));
+    }
+}
+
+#[test]
+fn wai_waits_for_interrupt() {
+    let mut cpu = common::new_cpu(&[WAI, NOP], Wdc);
+    cpu.p.remove(Status::I);
+
+    assert_eq!(cpu.step().state, RunState::Waiting);
+    for _ in 0..10 {
+        assert_eq!(cpu.step().state, RunState::Waiting);
+    }
+    assert_eq!(cpu.pc, PROGRAM_START + 1);
+
+    // The IRQ is recognized the same way as between instructions, so the CPU
+    // idles for two more cycles before handling it.
+    cpu.bus.schedule(Some(1), None);
+    assert_eq!(cpu.step().state, RunState::Waiting);
+    assert_eq!(cpu.step().state, RunState::Waiting);
+    assert_eq!(cpu.step().state, RunState::Running);
+    assert_eq!(cpu.pc, IRQ_HANDLER);
+}
+
+#[test]
+fn wai_resumes_with_interrupts_disabled() {
+    let program = [SEI, WAI, NOP];
+    let mut cpu = common::new_cpu(&program, Wdc);
+
+    cpu.step();
+    assert_eq!(cpu.step().state, RunState::Waiting);
+
+    cpu.bus.schedule(Some(1), None);
+    cpu.step();
+
+    assert_eq!(cpu.step().state, RunState::Running);
+    assert_eq!(cpu.pc, PROGRAM_START + 3);
+}
+
+#[test]
+fn stp_stops_until_reset() {
+    let mut cpu = common::new_cpu(&[STP], Wdc);
+
+    assert_eq!(cpu.step().state, RunState::Stopped);
+
+    // Interrupts don't wake the CPU up.
+    cpu.bus.schedule(Some(1), Some(1));
+    for _ in 0..10 {
+        assert_eq!(cpu.step().state, RunState::Stopped);
+    }
+
+    cpu.pins.rst = true;
+    cpu.step();
+    cpu.pins.rst = false;
+    cpu.step();
+
+    assert_eq!(cpu.run_state(), RunState::Running);
+    assert_eq!(cpu.pc, PROGRAM_START);
+}
+
+#[test]
+fn jam_halts_until_reset() {
+    let mut cpu = new_cpu(&[JAM]);
+    cpu.step();
+
+    assert!(cpu.is_jammed());
+    assert_eq!(cpu.run_state(), RunState::Jammed);
+
+    // Interrupts don't get the CPU out of it, and the address bus is stuck.
+    cpu.bus.schedule(Some(1), Some(1));
+    for _ in 0..10 {
+        assert_eq!(cpu.step().state, RunState::Jammed);
+        assert_eq!(cpu.pins.address, 0xffff);
+    }
+
+    cpu.pins.rst = true;
+    cpu.step();
+    cpu.pins.rst = false;
+    cpu.step();
+
+    assert!(!cpu.is_jammed());
+    assert_eq!(cpu.pc, PROGRAM_START);
+}
+
+#[test]
+fn reset_keeps_registers() {
+    // LDA #$42
+    let mut cpu = new_cpu(&[0xa9, 0x42]);
+    cpu.step();
+    let (s, cycles) = (cpu.s, cpu.cycles);
+
+    cpu.reset();
+
+    assert_eq!(cpu.cycles - cycles, 7);
+    assert_eq!(cpu.pc, PROGRAM_START);
+    assert_eq!(cpu.s, s.wrapping_sub(3));
+    assert_eq!(cpu.a, 0x42);
+    assert!(cpu.p.contains(Status::I));
+}
+
+#[test]
+fn reset_drops_pending_nmi() {
+    let mut cpu = new_cpu(&[NOP, NOP]);
+    cpu.bus.schedule(None, Some(2));
+    cpu.step();
+
+    cpu.reset();
+    cpu.step();
+
+    assert_eq!(cpu.pc, PROGRAM_START + 1);
+}
+
+#[test]
+fn power_on_clears_registers() {
+    // LDA #$42
+    let mut cpu = new_cpu(&[0xa9, 0x42]);
+    cpu.step();
+
+    cpu.power_on();
+
+    assert_eq!(cpu.cycles, 7);
+    assert_eq!(cpu.pc, PROGRAM_START);
+    assert_eq!(cpu.s, 0xfd);
+    assert_eq!(cpu.a, 0x00);
+}
+
+#[test]
+fn reset_aborts_instruction() {
+    // STA $10
+    let mut cpu = new_cpu(&[0x85, 0x10]);
+    cpu.a = 0x42;
+    cpu.bus.memory[0x10] = 0x00;
+
+    // The reset is seen during the opcode fetch, so the operand fetch and
+    // the write never happen.
+    cpu.tick();
+    cpu.pins.rst = true;
+    assert!(cpu.tick().rw);
+    cpu.pins.rst = false;
+    cpu.step();
+
+    assert_eq!(cpu.pc, PROGRAM_START);
+    assert_eq!(cpu.bus.memory[0x10], 0x00);
+}
+
+#[test]
+fn reset_leaves_registers_alone() {
+    // LDA $1234
+    let mut cpu = new_cpu(&[0xad, 0x34, 0x12]);
+    cpu.a = 0x42;
+    cpu.bus.memory[0x1234] = 0x99;
+
+    // The reset is seen during the operand fetches, so the read never happens
+    // and A keeps its value.
+    for _ in 0..3 {
+        cpu.tick();
+    }
+    cpu.pins.rst = true;
+    cpu.tick();
+    cpu.pins.rst = false;
+    cpu.step();
+
+    assert_eq!(cpu.a, 0x42);
+    assert_eq!(cpu.pc, PROGRAM_START);
+}
+
+#[test]
+fn branch_delays_interrupts() {
+    // A taken branch to the same page delays an interrupt detected during
+    // its operand fetch until after the next instruction.
+    for (offset, delayed) in [(0x00, true), (0x80, false)] {
+        for (irq_at, nmi_at, handler) in
+            [(Some(2), None, IRQ_HANDLER), (None, Some(2), NMI_HANDLER)]
+        {
+            let mut cpu = new_cpu(&[BEQ, offset, NOP]);
+            cpu.bus.memory[0x0182] = NOP;
+            cpu.p.insert(Status::Z);
+            cpu.bus.schedule(irq_at, nmi_at);
+
+            cpu.step();
+            cpu.step();
+
+            let expected = if delayed { PROGRAM_START + 3 } else { handler };
+            assert_eq!(cpu.pc, expected, "offset {offset:02X}");
+        }
+    }
+}
+
+#[test]
+fn interrupt_line_is_wired_or() {
+    let (via, timer) = (Source::new(1), Source::new(7));
+    let mut line = InterruptLine::default();
+    assert!(!line.is_asserted());
+
+    line.assert(via);
+    line.assert(timer);
+    assert!(line.sources().eq([via, timer]));
+
+    line.release(via);
+    assert!(line.is_asserted());
+    assert!(!line.is_asserted_by(via));
+    assert!(line.is_asserted_by(timer));
+
+    line.release(timer);
+    assert!(!line.is_asserted());
+
+    // The CPU takes the IRQ while any source still holds the line.
+    let mut cpu = new_cpu(&[NOP, NOP]);
+    cpu.pins.irq.assert(via);
+    cpu.pins.irq.assert(timer);
+    cpu.pins.irq.release(via);
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.pc, IRQ_HANDLER);
+}
+
+#[test]
+fn interrupt_sources_can_be_named() {
+    let via = Source::named(1, "VIA1");
+    let apu = Source::named(2, "APU frame");
+    let mapper = Source::named(3, "mapper");
+    let mut line = InterruptLine::default();
+    line.assert(apu);
+    line.assert(mapper);
+
+    let known = [via, apu, mapper];
+    let names = line.sources_among(&known).map(Source::name);
+    assert!(names.eq([Some("APU frame"), Some("mapper")]));
+
+    // Lines only keep IDs, which are all that sources are compared by.
+    assert!(line.sources().all(|source| source.name().is_none()));
+    assert!(line.sources().eq([apu, mapper]));
+    assert_eq!(Source::new(1), via);
+}
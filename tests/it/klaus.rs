@@ -1,23 +1,26 @@
 use std::fs;
 
-use bog::{Bus, Cpu, Pins};
+use bog::{Bus, Cpu, Pins, Source};
 
 const ZERO_PAGE_START: usize = 0xa;
 const CODE_SEGMENT_START: u16 = 0x400;
 const INTERRUPT_FEEDBACK_REGISTER: u16 = 0xbffc;
 const IRQ_MASK: u8 = 0x1;
 const NMI_MASK: u8 = 0x2;
+const FEEDBACK: Source = Source::new(0);
 const FUNCTIONAL_TEST_SUCCESS: u16 = 0x336d;
 const INTERRUPT_TEST_SUCCESS: u16 = 0x6f5;
+const DECIMAL_TEST_START: u16 = 0x200;
+const DECIMAL_TEST_ERROR: u16 = 0xb;
 
 struct KlausTestBus {
     memory: [u8; 0x10000],
 }
 
 impl KlausTestBus {
-    fn new(rom: &[u8]) -> KlausTestBus {
+    fn new(rom: &[u8], start: usize) -> KlausTestBus {
         let mut memory = [0; 0x10000];
-        memory[ZERO_PAGE_START..].copy_from_slice(rom);
+        memory[start..start + rom.len()].copy_from_slice(rom);
 
         KlausTestBus { memory }
     }
@@ -33,66 +36,85 @@ impl Bus for KlausTestBus {
                     let prev_nmi = old_data & NMI_MASK != 0;
                     let new_nmi = pins.data & NMI_MASK != 0;
 
-                    pins.irq = pins.data & IRQ_MASK != 0;
-                    pins.nmi = !prev_nmi && new_nmi;
+                    pins.irq.set(FEEDBACK, pins.data & IRQ_MASK != 0);
+                    pins.nmi.set(FEEDBACK, !prev_nmi && new_nmi);
                 }
 
                 self.memory[pins.address as usize] = pins.data;
             }
         }
     }
+
+    fn peek(&self, address: u16) -> Option<u8> {
+        Some(self.memory[address as usize])
+    }
+
+    // Unlike a write cycle, poking the feedback register doesn't trigger
+    // interrupts.
+    fn poke(&mut self, address: u16, data: u8) -> bool {
+        self.memory[address as usize] = data;
+        true
+    }
 }
 
 #[test]
 fn functional() {
     let rom = fs::read("roms/klaus/6502_functional_test.bin")
         .expect("roms/klaus/6502_functional_test.bin should exist");
-    let mut cpu = Cpu::new(KlausTestBus::new(&rom));
+    let mut cpu = Cpu::new(KlausTestBus::new(&rom, ZERO_PAGE_START));
 
     // Run through the reset sequence.
     cpu.step();
 
     cpu.pc = CODE_SEGMENT_START;
-    let mut prev_pc = cpu.pc;
-
-    loop {
-        cpu.step();
 
-        if prev_pc == cpu.pc {
-            if cpu.pc == FUNCTIONAL_TEST_SUCCESS {
-                break;
-            }
-
-            panic!("trapped at 0x{:04X}", cpu.pc);
-        }
-
-        prev_pc = cpu.pc;
-    }
+    cpu.run_until_trap();
+    assert!(!cpu.is_jammed(), "jammed at 0x{:04X}", cpu.pc);
+    assert_eq!(
+        cpu.pc, FUNCTIONAL_TEST_SUCCESS,
+        "trapped at 0x{:04X}",
+        cpu.pc
+    );
 }
 
 #[test]
 fn interrupt() {
     let rom = fs::read("roms/klaus/6502_interrupt_test.bin")
         .expect("roms/klaus/6502_interrupt_test.bin should exist");
-    let mut cpu = Cpu::new(KlausTestBus::new(&rom));
+    let mut cpu = Cpu::new(KlausTestBus::new(&rom, ZERO_PAGE_START));
 
     // Run through the reset sequence.
     cpu.step();
 
     cpu.pc = CODE_SEGMENT_START;
-    let mut prev_pc = cpu.pc;
 
-    loop {
-        cpu.step();
+    cpu.run_until_trap();
+    assert!(!cpu.is_jammed(), "jammed at 0x{:04X}", cpu.pc);
+    assert_eq!(
+        cpu.pc, INTERRUPT_TEST_SUCCESS,
+        "trapped at 0x{:04X}",
+        cpu.pc
+    );
+}
 
-        if prev_pc == cpu.pc {
-            if cpu.pc == INTERRUPT_TEST_SUCCESS {
-                break;
-            }
+#[test]
+fn decimal() {
+    let rom = fs::read("roms/klaus/6502_decimal_test.bin")
+        .expect("roms/klaus/6502_decimal_test.bin should exist");
+    let mut cpu = Cpu::new(KlausTestBus::new(&rom, 0));
 
-            panic!("trapped at 0x{:04X}", cpu.pc);
-        }
+    // Run through the reset sequence.
+    cpu.step();
 
-        prev_pc = cpu.pc;
-    }
+    cpu.pc = DECIMAL_TEST_START;
+
+    cpu.run_until_trap();
+    assert!(!cpu.is_jammed(), "jammed at 0x{:04X}", cpu.pc);
+
+    assert_eq!(
+        cpu.peek_memory(DECIMAL_TEST_ERROR),
+        Some(0),
+        "failed, trapped at 0x{:04X}",
+        cpu.pc
+    );
 }
@@ -1,3 +1,16 @@
+mod banked_memory;
+mod bus;
+mod common;
+mod decimal;
+mod inspector;
+mod interrupts;
 mod klaus;
+mod memory_map;
 mod nes;
+mod open_bus;
+mod pins;
 mod processor_tests;
+mod run;
+mod scheduler;
+mod tick;
+mod variants;
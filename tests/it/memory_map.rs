@@ -0,0 +1,110 @@
+use bog::{Bus, Cpu, MemoryMap, Pins, RomWrites};
+
+// A device with eight registers mirrored across its range, like the NES PPU.
+struct Registers {
+    registers: [u8; 8],
+}
+
+impl Bus for Registers {
+    fn tick(&mut self, pins: &mut Pins) {
+        let register = &mut self.registers[(pins.address & 0x07) as usize];
+        match pins.rw {
+            true => pins.data = *register,
+            false => *register = pins.data,
+        }
+    }
+}
+
+fn nes_rom() -> [u8; 0x4000] {
+    let mut rom = [0; 0x4000];
+    // LDA #$42; STA $0800; STA $2009; STA $8000; JMP $800C
+    let program = [
+        0xa9, 0x42, 0x8d, 0x00, 0x08, 0x8d, 0x09, 0x20, 0x8d, 0x00, 0x80,
+        0x4c, 0x0b, 0x80,
+    ];
+    rom[..program.len()].copy_from_slice(&program);
+    rom[0x3ffc] = 0x00;
+    rom[0x3ffd] = 0x80;
+    rom
+}
+
+#[test]
+fn nes_memory_map() {
+    let mut ram = [0; 0x0800];
+    let mut ppu = Registers { registers: [0; 8] };
+    let rom = nes_rom();
+
+    let mut map = MemoryMap::new();
+    map.ram(0x0000..=0x1fff, &mut ram);
+    map.device(0x2000..=0x3fff, &mut ppu);
+    map.rom(0x8000..=0xffff, &rom, RomWrites::Trap);
+
+    let mut cpu = Cpu::new(map);
+    cpu.run_until_trap();
+
+    assert_eq!(cpu.pc, 0x800b);
+    assert_eq!(cpu.bus.peek(0x0000), Some(0x42));
+    assert_eq!(cpu.bus.peek(0x1800), Some(0x42));
+    assert_eq!(cpu.bus.peek(0x2001), None);
+    assert_eq!(cpu.bus.peek(0xc000), Some(0xa9));
+    assert_eq!(cpu.bus.take_rom_write(), Some((0x8000, 0x42)));
+    assert_eq!(cpu.bus.take_rom_write(), None);
+
+    assert_eq!(ram[0], 0x42);
+    assert_eq!(ppu.registers[1], 0x42);
+}
+
+#[test]
+fn later_regions_take_priority() {
+    let mut low = [0x11; 0x10000];
+    let mut high = [0x22; 0x10];
+
+    let mut map = MemoryMap::new();
+    map.ram(0x0000..=0xffff, &mut low);
+    map.ram(0x1238..=0x1247, &mut high);
+
+    assert_eq!(map.peek(0x1237), Some(0x11));
+    assert_eq!(map.peek(0x1238), Some(0x22));
+    assert_eq!(map.peek(0x1247), Some(0x22));
+    assert_eq!(map.peek(0x1248), Some(0x11));
+
+    assert!(map.poke(0x1240, 0x33));
+    assert_eq!(map.peek(0x1240), Some(0x33));
+}
+
+#[test]
+fn unmapped_addresses() {
+    let mut ram = [0; 0x10];
+    let rom = [0xea; 0x10];
+
+    let mut map = MemoryMap::new();
+    map.ram(0x0000..=0x000f, &mut ram);
+    map.rom(0xfff0..=0xffff, &rom, RomWrites::Ignore);
+
+    // Reads from unmapped addresses leave the data bus alone.
+    let mut pins = Pins {
+        address: 0x4000,
+        data: 0x5a,
+        ..Pins::default()
+    };
+    map.tick(&mut pins);
+    assert_eq!(pins.data, 0x5a);
+    assert_eq!(map.peek(0x0010), None);
+    assert!(!map.poke(0x0010, 0x00));
+
+    // Ignored ROM writes aren't trapped, and ROM can't be poked.
+    pins.address = 0xfff0;
+    pins.rw = false;
+    map.tick(&mut pins);
+    assert_eq!(map.take_rom_write(), None);
+    assert!(!map.poke(0xfff0, 0x00));
+    assert_eq!(map.peek(0xfff0), Some(0xea));
+}
+
+#[test]
+#[should_panic(expected = "memory range is reversed")]
+fn reversed_ranges_are_rejected() {
+    let mut ram = [0; 0x100];
+    let (start, end) = (0x00ff, 0x0000);
+    MemoryMap::new().ram(start..=end, &mut ram);
+}
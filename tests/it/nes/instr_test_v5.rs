@@ -1,6 +1,6 @@
 use std::fs;
 
-use bog::Cpu;
+use bog::{Cpu, Ricoh2A03};
 
 use crate::nes::{NesBus, NromCartridge};
 
@@ -10,7 +10,7 @@ fn run(rom_filepath: &str) {
 
     let cartridge = NromCartridge::new(&rom);
     let bus = NesBus::new(cartridge);
-    let mut cpu = Cpu::new(bus);
+    let mut cpu = Cpu::with_variant(bus, Ricoh2A03);
 
     // Run through the reset sequence.
     cpu.step();
@@ -22,11 +22,13 @@ fn run(rom_filepath: &str) {
     let mut status = cpu.bus.cartridge.read_prg(STATUS_ADDRESS);
     while status != RUNNING {
         cpu.step();
+        assert!(!cpu.is_jammed(), "jammed at 0x{:04X}", cpu.pc);
         status = cpu.bus.cartridge.read_prg(STATUS_ADDRESS);
     }
 
     while status == RUNNING {
         cpu.step();
+        assert!(!cpu.is_jammed(), "jammed at 0x{:04X}", cpu.pc);
         status = cpu.bus.cartridge.read_prg(STATUS_ADDRESS);
     }
 
@@ -1,7 +1,7 @@
 mod instr_test_v5;
 mod nestest;
 
-use bog::{Bus, Pins};
+use bog::{Bus, OpenBus, Pins};
 
 const NES_RAM_SIZE: usize = 0x0800;
 
@@ -33,7 +33,7 @@ impl NromCartridge {
         }
     }
 
-    fn read_prg(&mut self, address: u16) -> u8 {
+    fn read_prg(&self, address: u16) -> u8 {
         match address {
             0x6000..=0x7fff => {
                 self.prg_ram[(address - 0x6000) as usize % self.prg_ram.len()]
@@ -54,11 +54,23 @@ impl NromCartridge {
             _ => unreachable!(),
         }
     }
+
+    fn poke_prg(&mut self, address: u16, data: u8) {
+        match address {
+            0x6000..=0x7fff => self.write_prg(address, data),
+            0x8000..=0xffff => {
+                let address = (address - 0x8000) as usize % self.prg_rom.len();
+                self.prg_rom[address] = data;
+            }
+            _ => unreachable!(),
+        }
+    }
 }
 
 struct NesBus {
     ram: [u8; NES_RAM_SIZE],
     pub cartridge: NromCartridge,
+    open_bus: OpenBus,
 }
 
 impl NesBus {
@@ -66,20 +78,20 @@ impl NesBus {
         NesBus {
             ram: [0; NES_RAM_SIZE],
             cartridge,
+            open_bus: OpenBus::new(),
         }
     }
 
     fn read(&mut self, pins: &mut Pins) {
-        match pins.address {
-            0x0000..=0x1fff => {
-                pins.data = self.ram[(pins.address & 0x07ff) as usize]
-            }
-            0x2000..=0x3fff => (),
-            0x4000..=0x401f => (),
-            0x4020..=0xffff => {
-                pins.data = self.cartridge.read_prg(pins.address)
-            }
-        }
+        pins.data = match pins.address {
+            0x0000..=0x1fff => self.ram[(pins.address & 0x07ff) as usize],
+            // The PPU, APU and I/O registers aren't emulated, and no
+            // cartridge here maps the expansion area, so nothing answers
+            // reads from them.
+            0x2000..=0x5fff => return self.open_bus.float(pins),
+            0x6000..=0xffff => self.cartridge.read_prg(pins.address),
+        };
+        self.open_bus.drive(pins);
     }
 
     fn write(&mut self, pins: &mut Pins) {
@@ -93,6 +105,7 @@ impl NesBus {
                 self.cartridge.write_prg(pins.address, pins.data)
             }
         }
+        self.open_bus.drive(pins);
     }
 }
 
@@ -103,4 +116,23 @@ impl Bus for NesBus {
             false => self.write(pins),
         }
     }
+
+    // The PPU and APU registers have side effects, so only RAM and the
+    // cartridge can be peeked and poked.
+    fn peek(&self, address: u16) -> Option<u8> {
+        match address {
+            0x0000..=0x1fff => Some(self.ram[(address & 0x07ff) as usize]),
+            0x2000..=0x5fff => None,
+            0x6000..=0xffff => Some(self.cartridge.read_prg(address)),
+        }
+    }
+
+    fn poke(&mut self, address: u16, data: u8) -> bool {
+        match address {
+            0x0000..=0x1fff => self.ram[(address & 0x07ff) as usize] = data,
+            0x2000..=0x5fff => return false,
+            0x6000..=0xffff => self.cartridge.poke_prg(address, data),
+        }
+        true
+    }
 }
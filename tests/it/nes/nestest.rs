@@ -4,7 +4,7 @@ use std::{
 };
 
 use bincode::Decode;
-use bog::{Cpu, Status};
+use bog::{Cpu, Ricoh2A03, Status};
 
 use crate::nes::{NesBus, NromCartridge};
 
@@ -28,7 +28,7 @@ fn nestest() {
 
     let cartridge = NromCartridge::new(&rom);
     let bus = NesBus::new(cartridge);
-    let mut cpu = Cpu::new(bus);
+    let mut cpu = Cpu::with_variant(bus, Ricoh2A03);
 
     // Run through the reset sequence.
     cpu.step();
@@ -57,5 +57,6 @@ fn nestest() {
         assert_eq!(cpu.cycles, state.cycles);
 
         cpu.step();
+        assert!(!cpu.is_jammed(), "jammed at 0x{:04X}", cpu.pc);
     }
 }
@@ -0,0 +1,85 @@
+use bog::{Bus, Cpu, OpenBus, Pins};
+
+use crate::common::{TestBus, PROGRAM_START};
+
+// The shared bus, with nothing mapped from 0x4000 on.
+struct OpenBusTestBus {
+    ram: TestBus,
+    open_bus: OpenBus,
+}
+
+impl Bus for OpenBusTestBus {
+    fn tick(&mut self, pins: &mut Pins) {
+        match (pins.rw, pins.address < 0x4000) {
+            (_, true) => self.ram.tick(pins),
+            (true, false) => return self.open_bus.float(pins),
+            (false, false) => (),
+        }
+        self.open_bus.drive(pins);
+    }
+}
+
+fn new_cpu(program: &[u8], open_bus: OpenBus) -> Cpu<OpenBusTestBus> {
+    let mut cpu = Cpu::new(OpenBusTestBus {
+        ram: TestBus::new(program),
+        open_bus,
+    });
+
+    // The vectors aren't mapped, so the reset sequence reads them from open
+    // bus.
+    cpu.step();
+    cpu.pc = PROGRAM_START;
+
+    cpu
+}
+
+#[test]
+fn unmapped_reads_return_the_last_value() {
+    // LDA $5000
+    let mut cpu = new_cpu(&[0xad, 0x00, 0x50], OpenBus::new());
+    cpu.step();
+
+    // The last value on the bus was the high byte of the operand.
+    assert_eq!(cpu.a, 0x50);
+}
+
+#[test]
+fn open_bus_decays() {
+    let mut open_bus = OpenBus::with_decay(3);
+    let mut pins = Pins {
+        data: 0xff,
+        ..Pins::default()
+    };
+    open_bus.drive(&pins);
+    pins.data = 0x00;
+    open_bus.drive_bits(0x00, 0x0f);
+    assert_eq!(open_bus.value(), 0xf0);
+
+    open_bus.float(&mut pins);
+    assert_eq!(pins.data, 0xf0);
+
+    // The high bits were driven three cycles ago.
+    open_bus.float(&mut pins);
+    assert_eq!(pins.data, 0x00);
+
+    // Wait states count towards the decay.
+    pins.data = 0xff;
+    open_bus.drive(&pins);
+    open_bus.wait(2);
+    open_bus.float(&mut pins);
+    assert_eq!(pins.data, 0x00);
+}
+
+#[test]
+fn open_bus_without_decay() {
+    let mut open_bus = OpenBus::new();
+    let mut pins = Pins {
+        data: 0xa5,
+        ..Pins::default()
+    };
+    open_bus.drive(&pins);
+    for _ in 0..1000 {
+        open_bus.float(&mut pins);
+    }
+    assert_eq!(pins.data, 0xa5);
+}
@@ -0,0 +1,239 @@
+use std::ops::Range;
+
+use bog::{
+    Bus, Cmos, Cpu, CycleKind, Mos6507, Mos6510, Nmos, Pins, Rockwell,
+    ShModel, Status, Variant, Wdc,
+};
+
+use crate::common::{TestBus, PROGRAM_START};
+
+// The shared bus, plus RDY, SO and wait states.
+struct PinsTestBus {
+    ram: TestBus,
+    // The ticks, counting from one, during which RDY is held low.
+    rdy_low: Range<usize>,
+    so_at: Option<usize>,
+    // The addresses that take two wait states.
+    slow: Range<u16>,
+}
+
+impl Bus for PinsTestBus {
+    fn tick(&mut self, pins: &mut Pins) {
+        self.ram.tick(pins);
+
+        let ticks = self.ram.ticks();
+        pins.rdy = !self.rdy_low.contains(&ticks);
+        pins.so = self.so_at.is_some_and(|tick| ticks >= tick);
+        if self.slow.contains(&pins.address) {
+            pins.wait = 2;
+        }
+    }
+}
+
+fn new_cpu<V: Variant>(program: &[u8], variant: V) -> Cpu<PinsTestBus, V> {
+    let bus = PinsTestBus {
+        ram: TestBus::new(program),
+        rdy_low: 0..0,
+        so_at: None,
+        slow: 0..0,
+    };
+    let mut cpu = Cpu::with_variant(bus, variant);
+
+    // Run through the reset sequence.
+    cpu.step();
+    cpu.bus.ram.accesses.clear();
+
+    cpu
+}
+
+#[test]
+fn rdy_stalls_reads() {
+    // LDA $1234
+    let mut cpu = new_cpu(&[0xad, 0x34, 0x12], Nmos);
+    cpu.bus.ram.memory[0x1234] = 0x42;
+    cpu.bus.rdy_low = 2..5;
+
+    assert_eq!(cpu.step().cycles, 7);
+    assert_eq!(cpu.a, 0x42);
+    assert_eq!(cpu.pc, PROGRAM_START + 3);
+}
+
+#[test]
+fn rdy_does_not_stall_writes() {
+    // STA $1234; NOP
+    let mut cpu = new_cpu(&[0x8d, 0x34, 0x12, 0xea], Nmos);
+    cpu.a = 0x42;
+    cpu.bus.rdy_low = 4..6;
+
+    assert_eq!(cpu.step().cycles, 4);
+    assert_eq!(cpu.bus.ram.memory[0x1234], 0x42);
+
+    // The opcode fetch of the next instruction is stalled instead.
+    assert_eq!(cpu.step().cycles, 3);
+}
+
+#[test]
+fn rdy_drops_sha_high_byte() {
+    // SHA $1200,Y
+    let program = [0x9f, 0x00, 0x12];
+    for (sh, rdy_low, address, value) in [
+        (ShModel::PageCross, 0..0, 0x1234, 0x12),
+        (ShModel::PageCross, 4..5, 0x1234, 0x92),
+        (ShModel::Always, 0..0, 0x1234, 0x12),
+        (ShModel::Always, 4..5, 0x9234, 0x92),
+    ] {
+        let mut cpu = new_cpu(&program, Nmos);
+        cpu.unstable.sh = sh;
+        cpu.a = 0x92;
+        cpu.x = 0xff;
+        cpu.y = 0x34;
+        cpu.bus.rdy_low = rdy_low;
+
+        cpu.step();
+
+        assert_eq!(cpu.bus.ram.memory[address], value, "{sh:?}");
+    }
+}
+
+#[test]
+fn rdy_stalls_tick() {
+    // LDA $1234; INC $10
+    let program = [0xad, 0x34, 0x12, 0xe6, 0x10];
+    let mut stepped = new_cpu(&program, Nmos);
+    stepped.bus.ram.memory[0x1234] = 0x42;
+    stepped.bus.rdy_low = 3..100;
+    stepped.step();
+    stepped.step();
+
+    let mut ticked = new_cpu(&program, Nmos);
+    ticked.bus.ram.memory[0x1234] = 0x42;
+    ticked.bus.rdy_low = 3..100;
+    while ticked.cycles < stepped.cycles {
+        ticked.tick();
+    }
+
+    assert_eq!(ticked.cycles, stepped.cycles);
+    assert_eq!(ticked.pc, stepped.pc);
+    assert_eq!(ticked.a, 0x42);
+    assert_eq!(ticked.bus.ram.memory[0x10], 1);
+}
+
+#[test]
+fn so_sets_overflow() {
+    // CLV; BVC -2; CLV
+    let mut cpu = new_cpu(&[0xb8, 0x50, 0xfe, 0xb8], Nmos);
+    cpu.bus.so_at = Some(20);
+    cpu.step();
+
+    while cpu.pc == PROGRAM_START + 1 {
+        cpu.step();
+    }
+    assert_eq!(cpu.pc, PROGRAM_START + 3);
+    assert!(cpu.p.contains(Status::V));
+
+    // Only the edge sets V, so holding SO doesn't set it again.
+    cpu.step();
+    cpu.step();
+    assert!(!cpu.p.contains(Status::V));
+}
+
+#[test]
+fn so_is_missing_on_6507_and_6510() {
+    fn check<V: Variant>(variant: V) {
+        // CLV; NOP; NOP
+        let mut cpu = new_cpu(&[0xb8, 0xea, 0xea], variant);
+        cpu.bus.so_at = Some(3);
+        for _ in 0..3 {
+            cpu.step();
+        }
+        assert!(!cpu.p.contains(Status::V));
+    }
+    check(Mos6507);
+    check(Mos6510);
+}
+
+#[test]
+fn sync_marks_opcode_fetches() {
+    // LDA #$01; INC $10
+    let mut cpu = new_cpu(&[0xa9, 0x01, 0xe6, 0x10], Nmos);
+    let sync: Vec<_> = (0..7).map(|_| cpu.tick().sync).collect();
+
+    assert_eq!(sync, [true, false, true, false, false, false, false]);
+}
+
+#[test]
+fn wdc_vp_and_ml() {
+    // INC $10; BRK
+    let program = [0xe6, 0x10, 0x00];
+
+    let mut cpu = new_cpu(&program, Wdc);
+    let pins: Vec<_> = (0..12).map(|_| cpu.tick()).collect();
+    let ml: Vec<_> = pins.iter().map(|pins| pins.ml).collect();
+    let vp: Vec<_> = pins.iter().map(|pins| pins.vp).collect();
+    assert_eq!(ml[..5], [false, false, true, true, true]);
+    assert!(ml[5..].iter().all(|&ml| !ml));
+    assert!(vp[..10].iter().all(|&vp| !vp));
+    assert_eq!(vp[10..], [true, true]);
+
+    // The other chips don't have them.
+    fn check<V: Variant>(program: &[u8], variant: V) {
+        let mut cpu = new_cpu(program, variant);
+        for _ in 0..12 {
+            let pins = cpu.tick();
+            assert!(!pins.ml && !pins.vp);
+        }
+    }
+    check(&program, Nmos);
+    check(&program, Cmos);
+    check(&program, Rockwell);
+}
+
+#[test]
+fn cycle_kinds() {
+    use CycleKind::*;
+
+    // LDA $10FF,X; INC $10; JSR $0300
+    let program = [0xbd, 0xff, 0x10, 0xe6, 0x10, 0x20, 0x00, 0x03];
+    let mut cpu = new_cpu(&program, Nmos);
+    cpu.x = 0x01;
+    let kinds: Vec<_> = (0..16).map(|_| cpu.tick().kind).collect();
+
+    assert_eq!(
+        kinds,
+        [
+            Opcode, Operand, Operand, DummyRead, Read, // LDA
+            Opcode, Operand, Read, DummyWrite, Write, // INC
+            Opcode, Operand, DummyRead, Push, Push, Operand, // JSR
+        ]
+    );
+
+    // BRK
+    let mut cpu = new_cpu(&[0x00], Nmos);
+    let kinds: Vec<_> = (0..7).map(|_| cpu.tick().kind).collect();
+
+    assert_eq!(kinds, [Opcode, DummyRead, Push, Push, Push, Vector, Vector]);
+}
+
+#[test]
+fn wait_states_add_cycles() {
+    // LDA $8000; NOP
+    let mut cpu = new_cpu(&[0xad, 0x00, 0x80, 0xea], Nmos);
+    cpu.bus.slow = 0x8000..0x8001;
+
+    assert_eq!(cpu.step().cycles, 6);
+    assert_eq!(cpu.step().cycles, 2);
+    assert_eq!(cpu.bus.ram.ticks(), 6);
+
+    // Tick mode counts them the same way.
+    let mut cpu = new_cpu(&[0xad, 0x00, 0x80, 0xea], Nmos);
+    cpu.bus.slow = 0x8000..0x8001;
+    let start = cpu.cycles;
+    let wait: Vec<_> = (0..3).map(|_| cpu.tick().wait).collect();
+    assert_eq!(wait, [0, 0, 0]);
+    assert_eq!(cpu.cycles - start, 3);
+
+    assert_eq!(cpu.tick().wait, 2);
+    assert_eq!(cpu.cycles - start, 6);
+    cpu.tick();
+    assert_eq!(cpu.cycles - start, 7);
+}
@@ -1,7 +1,10 @@
 use std::{fs::File, io::BufReader};
 
 use bincode::Decode;
-use bog::{Bus, Cpu, Pins, Status};
+use bog::{
+    Bus, Cmos, Cpu, CycleKind, Nmos, Pins, Rockwell, RunState, Status,
+    Variant, Wdc,
+};
 
 #[derive(Decode)]
 struct State {
@@ -23,7 +26,6 @@ struct Cycle {
 
 #[derive(Decode)]
 struct Test {
-    #[allow(dead_code)]
     name: String,
     initial: State,
     r#final: State,
@@ -60,6 +62,8 @@ impl Bus for ProcessorTestBus {
 
         if let Some(cycle) = self.cycles.get(self.index) {
             assert_eq!(if pins.rw { "read" } else { "write" }, cycle.kind);
+            assert_eq!(pins.kind.is_write(), !pins.rw);
+            assert_eq!(pins.kind == CycleKind::Opcode, self.index == 0);
             assert_eq!(pins.address, cycle.address);
             assert_eq!(pins.data, cycle.data);
             self.index += 1;
@@ -74,7 +78,11 @@ fn run(opcode: u8) {
         return;
     }
 
-    let filename = format!("roms/ProcessorTests/{:02x}.bincode", opcode);
+    run_variant(Nmos, "roms/ProcessorTests", opcode);
+}
+
+fn run_variant<V: Variant + Copy>(variant: V, directory: &str, opcode: u8) {
+    let filename = format!("{}/{:02x}.bincode", directory, opcode);
     let file = File::open(&filename)
         .unwrap_or_else(|_| panic!("{} should exist", &filename));
     let mut buf_reader = BufReader::new(file);
@@ -84,7 +92,7 @@ fn run(opcode: u8) {
     )
     .unwrap();
 
-    let mut cpu = Cpu::new(ProcessorTestBus::new());
+    let mut cpu = Cpu::with_variant(ProcessorTestBus::new(), variant);
 
     // Run through the reset sequence.
     cpu.step();
@@ -104,15 +112,31 @@ fn run(opcode: u8) {
 
         cpu.step();
 
+        // A halted CPU keeps running cycles of its own.
+        while cpu.run_state() != RunState::Running
+            && cpu.bus.index < cpu.bus.cycles.len()
+        {
+            cpu.step();
+        }
+
+        let name = test.name;
+        assert_eq!(cpu.bus.index, cpu.bus.cycles.len(), "{name}");
+
         let r#final = test.r#final;
-        assert_eq!(cpu.pc, r#final.pc);
-        assert_eq!(cpu.s, r#final.s);
-        assert_eq!(cpu.a, r#final.a);
-        assert_eq!(cpu.x, r#final.x);
-        assert_eq!(cpu.y, r#final.y);
-        assert_eq!(cpu.p.bits(), r#final.p);
+        assert_eq!(cpu.pc, r#final.pc, "{name}");
+        assert_eq!(cpu.s, r#final.s, "{name}");
+        assert_eq!(cpu.a, r#final.a, "{name}");
+        assert_eq!(cpu.x, r#final.x, "{name}");
+        assert_eq!(cpu.y, r#final.y, "{name}");
+        assert_eq!(cpu.p.bits(), r#final.p, "{name}");
         for [address, data] in r#final.ram {
-            assert_eq!(cpu.bus.memory[address as usize], data as u8);
+            assert_eq!(cpu.bus.memory[address as usize], data as u8, "{name}");
+        }
+
+        // Start over if the instruction halted the CPU.
+        if cpu.run_state() != RunState::Running {
+            cpu = Cpu::with_variant(ProcessorTestBus::new(), variant);
+            cpu.step();
         }
     }
 }
@@ -1291,3 +1315,24 @@ fn opcode_fe() {
 fn opcode_ff() {
     run(0xff);
 }
+
+#[test]
+fn cmos() {
+    for opcode in 0..=0xff {
+        run_variant(Cmos, "roms/ProcessorTests/synertek65c02", opcode);
+    }
+}
+
+#[test]
+fn rockwell() {
+    for opcode in 0..=0xff {
+        run_variant(Rockwell, "roms/ProcessorTests/rockwell65c02", opcode);
+    }
+}
+
+#[test]
+fn wdc() {
+    for opcode in 0..=0xff {
+        run_variant(Wdc, "roms/ProcessorTests/wdc65c02", opcode);
+    }
+}
@@ -0,0 +1,79 @@
+use bog::{Cpu, Interrupt, Nmos, RunState, Wdc};
+
+use crate::common::{new_cpu, TestBus, PROGRAM_START};
+
+#[test]
+fn step_result() {
+    // LDA #$01; JMP $0202
+    let mut cpu = Cpu::new(TestBus::new(&[0xa9, 0x01, 0x4c, 0x02, 0x02]));
+
+    let result = cpu.step();
+    assert_eq!(result.interrupt, Some(Interrupt::Reset));
+    assert_eq!(result.opcode, None);
+    assert_eq!(result.cycles, 7);
+
+    let result = cpu.step();
+    assert_eq!(result.opcode, Some(0xa9));
+    assert_eq!(result.interrupt, None);
+    assert_eq!(result.cycles, 2);
+    assert_eq!(result.state, RunState::Running);
+    assert!(!result.trapped);
+
+    let result = cpu.step();
+    assert_eq!(result.opcode, Some(0x4c));
+    assert!(result.trapped);
+}
+
+#[test]
+fn run_for_cycles_overshoots() {
+    // NOP; LDA $10; NOP
+    let mut cpu = new_cpu(&[0xea, 0xa5, 0x10, 0xea], Nmos);
+
+    assert_eq!(cpu.run_for_cycles(3), 2);
+    assert_eq!(cpu.pc, PROGRAM_START + 3);
+    assert_eq!(cpu.run_for_cycles(2), 0);
+}
+
+#[test]
+fn run_until() {
+    // CLC; ADC #$01; JMP $0200
+    let mut cpu = new_cpu(&[0x18, 0x69, 0x01, 0x4c, 0x00, 0x02], Nmos);
+
+    let result = cpu.run_until(|cpu| cpu.a == 3);
+    assert_eq!(result.opcode, Some(0x69));
+    assert_eq!(cpu.a, 3);
+}
+
+#[test]
+fn run_until_trap() {
+    // LDX #$05; DEX; BNE -3; BEQ -2
+    let mut cpu = new_cpu(&[0xa2, 0x05, 0xca, 0xd0, 0xfd, 0xf0, 0xfe], Nmos);
+
+    let result = cpu.run_until_trap();
+    assert!(result.trapped);
+    assert_eq!(cpu.pc, PROGRAM_START + 5);
+
+    // JAM
+    let mut cpu = new_cpu(&[0x02], Nmos);
+
+    let result = cpu.run_until_trap();
+    assert!(!result.trapped);
+    assert_eq!(result.state, RunState::Jammed);
+}
+
+#[test]
+fn run_until_trap_halts() {
+    // NOP; STP
+    let mut cpu = new_cpu(&[0xea, 0xdb], Wdc);
+
+    let result = cpu.run_until_trap();
+    assert!(!result.trapped);
+    assert_eq!(result.state, RunState::Stopped);
+    assert_eq!(cpu.pc, PROGRAM_START + 2);
+
+    // WAI
+    let mut cpu = new_cpu(&[0xcb], Wdc);
+
+    let result = cpu.run_until_trap();
+    assert_eq!(result.state, RunState::Waiting);
+}
@@ -0,0 +1,164 @@
+use bog::{Bus, Cpu, Pins, Scheduler, Source};
+
+use crate::common::{TestBus, IRQ_HANDLER};
+
+const TIMER_START: u16 = 0x4000;
+const TIMER_ACK: u16 = 0x4001;
+const TIMER: Source = Source::new(0);
+
+#[derive(Debug, PartialEq, Eq)]
+enum Event {
+    Timer,
+    Other(u8),
+}
+
+// A bus with a one-shot timer that raises an IRQ, which it schedules instead
+// of counting down every cycle. It can also raise the IRQ itself, to check
+// the timing against.
+struct SchedulerTestBus {
+    ram: TestBus,
+    cycles: u64,
+    scheduler: Scheduler<Event, 4>,
+    started_at: Option<u64>,
+    acked_at: Option<u64>,
+    irq_at: Option<u64>,
+}
+
+impl Bus for SchedulerTestBus {
+    fn tick(&mut self, pins: &mut Pins) {
+        self.cycles += 1;
+
+        match (pins.rw, pins.address) {
+            (false, TIMER_START) => {
+                self.scheduler.schedule_in(
+                    self.cycles,
+                    pins.data as u64,
+                    Event::Timer,
+                );
+                self.started_at = Some(self.cycles);
+            }
+            (true, TIMER_ACK) => {
+                pins.data = 0x80;
+                pins.irq.release(TIMER);
+                self.acked_at = Some(self.cycles);
+            }
+            _ => self.ram.tick(pins),
+        }
+
+        if self.irq_at == Some(self.cycles) {
+            pins.irq.assert(TIMER);
+        }
+    }
+}
+
+fn new_cpu(delay: u8) -> Cpu<SchedulerTestBus> {
+    // LDA #delay; STA $4000; CLI; JMP $0206
+    let mut ram =
+        TestBus::new(&[0xa9, delay, 0x8d, 0x00, 0x40, 0x58, 0x4c, 0x06, 0x02]);
+    // LDA $4001; STA $10; JMP $0405
+    let handler = [0xad, 0x01, 0x40, 0x85, 0x10, 0x4c, 0x05, 0x04];
+    let start = IRQ_HANDLER as usize;
+    ram.memory[start..start + handler.len()].copy_from_slice(&handler);
+
+    Cpu::new(SchedulerTestBus {
+        ram,
+        cycles: 0,
+        scheduler: Scheduler::new(),
+        started_at: None,
+        acked_at: None,
+        irq_at: None,
+    })
+}
+
+#[test]
+fn timer_interrupt() {
+    let mut cpu = new_cpu(0x20);
+
+    let mut fired_at = None;
+    while cpu.cycles < 200 {
+        if let Some(cycle) = cpu.bus.scheduler.next_cycle() {
+            assert_eq!(cpu.run_until_cycle(cycle), 0);
+        } else {
+            cpu.step();
+        }
+
+        while let Some((cycle, event)) = cpu.bus.scheduler.pop(cpu.cycles) {
+            assert_eq!(event, Event::Timer);
+            assert_eq!(cpu.cycles, cycle);
+            fired_at = Some(cycle);
+            cpu.pins.irq.assert(TIMER);
+        }
+    }
+
+    let started_at = cpu.bus.started_at.unwrap();
+    assert_eq!(fired_at, Some(started_at + 0x20));
+    assert_eq!(cpu.bus.ram.memory[0x10], 0x80);
+    assert_eq!(cpu.pc, IRQ_HANDLER + 5);
+    assert!(!cpu.pins.irq.is_asserted());
+    assert!(cpu.bus.scheduler.is_empty());
+}
+
+#[test]
+fn scheduled_interrupts_are_cycle_exact() {
+    // The IRQ lands on every cycle of the JMP loop, so it's taken after
+    // different instructions.
+    for delay in 0x20..0x26 {
+        let mut scheduled = new_cpu(delay);
+        while scheduled.cycles < 200 {
+            if let Some(cycle) = scheduled.bus.scheduler.next_cycle() {
+                scheduled.run_until_cycle(cycle);
+            } else {
+                scheduled.step();
+            }
+
+            if scheduled.bus.scheduler.pop(scheduled.cycles).is_some() {
+                scheduled.pins.irq.assert(TIMER);
+            }
+        }
+
+        // The same IRQ, raised by the bus on the cycle it's due.
+        let mut raised = new_cpu(delay);
+        while raised.cycles < 200 {
+            raised.step();
+            if let Some(started_at) = raised.bus.started_at.take() {
+                raised.bus.irq_at = Some(started_at + delay as u64);
+            }
+        }
+
+        assert!(scheduled.bus.acked_at.is_some(), "delay {delay}");
+        assert_eq!(
+            scheduled.bus.acked_at, raised.bus.acked_at,
+            "delay {delay}"
+        );
+    }
+}
+
+#[test]
+fn events_come_out_in_order() {
+    let mut scheduler: Scheduler<Event, 4> = Scheduler::new();
+    scheduler.schedule(30, Event::Other(0));
+    scheduler.schedule_in(5, 5, Event::Other(1));
+    scheduler.schedule(10, Event::Other(2));
+    scheduler.schedule(20, Event::Timer);
+    assert_eq!(scheduler.len(), 4);
+    assert_eq!(scheduler.next_cycle(), Some(10));
+
+    assert_eq!(scheduler.pop(9), None);
+    assert_eq!(scheduler.pop(10), Some((10, Event::Other(1))));
+    assert_eq!(scheduler.pop(10), Some((10, Event::Other(2))));
+    assert_eq!(scheduler.pop(10), None);
+
+    scheduler.cancel(|event| *event == Event::Timer);
+    assert_eq!(scheduler.next_cycle(), Some(30));
+    assert_eq!(scheduler.pop(100), Some((30, Event::Other(0))));
+    assert!(scheduler.is_empty());
+    assert_eq!(scheduler.next_cycle(), None);
+}
+
+#[test]
+#[should_panic(expected = "scheduler is full")]
+fn scheduler_is_full() {
+    let mut scheduler: Scheduler<Event, 1> = Scheduler::new();
+    scheduler.schedule(0, Event::Timer);
+    scheduler.schedule(0, Event::Timer);
+}
@@ -0,0 +1,153 @@
+use bog::{Cpu, Nmos, Status, Variant, Wdc};
+
+use crate::common::{with_bus, TestBus, DEVICE, IRQ_HANDLER, PROGRAM_START};
+
+const NOP: u8 = 0xea;
+
+// A xorshift generator, so that the memory contents are the same every run.
+fn random_memory(seed: &mut u32) -> [u8; 0x10000] {
+    let mut memory = [0; 0x10000];
+    for byte in memory.iter_mut() {
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 17;
+        *seed ^= *seed << 5;
+        *byte = *seed as u8;
+    }
+
+    memory
+}
+
+// Constructs a CPU with `program` followed by NOPs.
+fn nop_cpu(program: &[u8]) -> Cpu<TestBus> {
+    with_bus(TestBus::with_memory([NOP; 0x10000], program), Nmos)
+}
+
+// Runs a few instructions starting with each opcode using both `step` and
+// `tick` and checks that the bus sees the same cycles.
+fn run<V: Variant + Copy>(variant: V) {
+    let mut seed = 0x6502;
+    for opcode in 0..=0xff {
+        let memory = random_memory(&mut seed);
+
+        let bus = TestBus::with_memory(memory, &[opcode]);
+        let mut stepped = with_bus(bus, variant);
+        for _ in 0..4 {
+            stepped.step();
+        }
+
+        let bus = TestBus::with_memory(memory, &[opcode]);
+        let mut ticked = with_bus(bus, variant);
+        let mut pins = Vec::new();
+        while ticked.cycles < stepped.cycles {
+            let tick = ticked.tick();
+            pins.push((tick.address, tick.data, tick.rw));
+        }
+
+        let context = format!("opcode {opcode:02X}");
+        assert_eq!(pins, stepped.bus.accesses, "{context}");
+        assert_eq!(ticked.bus.accesses, stepped.bus.accesses, "{context}");
+        assert_eq!(ticked.cycles, stepped.cycles, "{context}");
+        assert_eq!(ticked.pc, stepped.pc, "{context}");
+        assert_eq!(ticked.s, stepped.s, "{context}");
+        assert_eq!(ticked.a, stepped.a, "{context}");
+        assert_eq!(ticked.x, stepped.x, "{context}");
+        assert_eq!(ticked.y, stepped.y, "{context}");
+        assert_eq!(ticked.p.bits(), stepped.p.bits(), "{context}");
+    }
+}
+
+#[test]
+fn nmos_tick_matches_step() {
+    run(Nmos);
+}
+
+#[test]
+fn wdc_tick_matches_step() {
+    run(Wdc);
+}
+
+#[test]
+fn tick_runs_one_cycle() {
+    // LDA $1234; NOP
+    let mut cpu = nop_cpu(&[0xad, 0x34, 0x12, NOP]);
+    cpu.bus.memory[0x1234] = 0x42;
+
+    for (i, address) in
+        [0x0200, 0x0201, 0x0202, 0x1234].into_iter().enumerate()
+    {
+        assert_eq!(cpu.tick().address, address);
+        assert_eq!(cpu.bus.accesses.len(), i + 1);
+    }
+    assert_eq!(cpu.a, 0x42);
+    assert_eq!(cpu.pc, PROGRAM_START + 3);
+
+    // Finish the NOP that was started with `tick`.
+    cpu.tick();
+    cpu.step();
+    assert_eq!(cpu.pc, PROGRAM_START + 4);
+    assert_eq!(cpu.bus.accesses.len(), 6);
+}
+
+#[test]
+fn tick_shows_registers_between_cycles() {
+    // LDA $1234
+    let mut cpu = nop_cpu(&[0xad, 0x34, 0x12]);
+    let cycles = cpu.cycles;
+
+    for (i, pc) in [PROGRAM_START + 1, PROGRAM_START + 2, PROGRAM_START + 3]
+        .into_iter()
+        .enumerate()
+    {
+        cpu.tick();
+        assert_eq!(cpu.pc, pc);
+        assert_eq!(cpu.cycles, cycles + i as u64 + 1);
+    }
+}
+
+#[test]
+fn tick_keeps_cycle_changes() {
+    let mut cpu = nop_cpu(&[]);
+
+    cpu.tick();
+    cpu.cycles = 1000;
+    cpu.tick();
+    assert_eq!(cpu.cycles, 1001);
+    cpu.step();
+    assert_eq!(cpu.cycles, 1003);
+}
+
+#[test]
+fn tick_restarts_after_register_changes() {
+    let mut cpu = nop_cpu(&[]);
+
+    cpu.tick();
+    cpu.pc = 0x0300;
+    let pins = cpu.tick();
+    assert_eq!(pins.address, 0x0300);
+    assert!(pins.sync);
+    assert_eq!(cpu.pc, 0x0301);
+}
+
+#[test]
+fn tick_sees_pin_changes() {
+    for tick in 1..=6 {
+        let mut stepped = nop_cpu(&[]);
+        stepped.p.remove(Status::I);
+        stepped.bus.irq_at = Some(tick);
+        while stepped.pc < IRQ_HANDLER {
+            stepped.step();
+        }
+
+        let mut ticked = nop_cpu(&[]);
+        ticked.p.remove(Status::I);
+        for _ in 0..tick {
+            ticked.tick();
+        }
+        ticked.pins.irq.assert(DEVICE);
+        while ticked.pc < IRQ_HANDLER {
+            ticked.tick();
+        }
+
+        assert_eq!(ticked.cycles, stepped.cycles, "IRQ at cycle {tick}");
+    }
+}
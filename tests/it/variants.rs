@@ -0,0 +1,306 @@
+use bog::{
+    Cmos, Mos6507, Mos6510, Nmos, RevisionA, Ricoh2A03, Rockwell, ShModel,
+    Source, Status, Unstable,
+};
+
+use crate::common::{new_cpu, PROGRAM_START};
+
+#[test]
+fn ricoh_2a03_ignores_decimal_flag() {
+    // SED; LDA #$09; ADC #$01
+    let mut cpu = new_cpu(&[0xf8, 0xa9, 0x09, 0x69, 0x01], Ricoh2A03);
+    cpu.step();
+    cpu.step();
+    cpu.step();
+
+    assert_eq!(cpu.a, 0x0a);
+}
+
+#[test]
+fn nmos_uses_decimal_flag() {
+    // SED; LDA #$09; ADC #$01
+    let mut cpu = new_cpu(&[0xf8, 0xa9, 0x09, 0x69, 0x01], Nmos);
+    cpu.step();
+    cpu.step();
+    cpu.step();
+
+    assert_eq!(cpu.a, 0x10);
+}
+
+#[test]
+fn revision_a_ror_shifts_left() {
+    // SEC; LDA #$81; ROR A
+    let mut cpu = new_cpu(&[0x38, 0xa9, 0x81, 0x6a], RevisionA);
+    cpu.step();
+    cpu.step();
+    cpu.step();
+
+    assert_eq!(cpu.a, 0x02);
+    assert!(cpu.p.contains(Status::C));
+}
+
+#[test]
+fn cmos_jmp_indirect_crosses_page() {
+    // JMP ($02FF)
+    let mut cpu = new_cpu(&[0x6c, 0xff, 0x02], Cmos);
+    cpu.bus.memory[0x02ff] = 0x00;
+    cpu.bus.memory[0x0300] = 0x04;
+
+    assert_eq!(cpu.step().cycles, 6);
+    assert_eq!(cpu.pc, 0x0400);
+}
+
+#[test]
+fn cmos_decimal_flags_are_valid() {
+    // SED; LDA #$99; ADC #$01
+    let mut cpu = new_cpu(&[0xf8, 0xa9, 0x99, 0x69, 0x01], Cmos);
+    cpu.step();
+    cpu.step();
+
+    assert_eq!(cpu.step().cycles, 3);
+    assert_eq!(cpu.a, 0x00);
+    assert!(cpu.p.contains(Status::Z));
+    assert!(cpu.p.contains(Status::C));
+}
+
+#[test]
+fn cmos_instructions() {
+    let program = [
+        0xa9, 0x0f, // LDA #$0F
+        0x64, 0x10, // STZ $10
+        0x04, 0x10, // TSB $10
+        0xa9, 0x03, // LDA #$03
+        0x14, 0x10, // TRB $10
+        0x1a, // INC A
+        0xda, // PHX
+        0x7a, // PLY
+        0xb2, 0x20, // LDA ($20)
+        0x80, 0x01, // BRA +1
+        0x00, // BRK
+        0x89, 0x80, // BIT #$80
+    ];
+    let mut cpu = new_cpu(&program, Cmos);
+    cpu.bus.memory[0x10] = 0xff;
+    cpu.bus.memory[0x20] = 0x00;
+    cpu.bus.memory[0x21] = 0x03;
+    cpu.bus.memory[0x0300] = 0x80;
+    cpu.x = 0x42;
+
+    for _ in 0..5 {
+        cpu.step();
+    }
+    assert_eq!(cpu.bus.memory[0x10], 0x0c);
+    assert!(!cpu.p.contains(Status::Z));
+
+    cpu.step();
+    assert_eq!(cpu.a, 0x04);
+
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.y, 0x42);
+
+    cpu.step();
+    assert_eq!(cpu.a, 0x80);
+
+    assert_eq!(cpu.step().cycles, 3);
+    assert_eq!(cpu.pc, PROGRAM_START + 18);
+
+    cpu.step();
+    assert!(cpu.p.contains(Status::N));
+    assert!(!cpu.p.contains(Status::Z));
+}
+
+#[test]
+fn cmos_unused_opcodes_are_nops() {
+    for (opcode, length, cycles) in [
+        (0x02, 2, 2),
+        (0x03, 1, 1),
+        (0x44, 2, 3),
+        (0x54, 2, 4),
+        (0x5c, 3, 8),
+        (0xdc, 3, 4),
+    ] {
+        let mut cpu = new_cpu(&[opcode], Cmos);
+        let (a, x, y, s, p) = (cpu.a, cpu.x, cpu.y, cpu.s, cpu.p.bits());
+
+        assert_eq!(cpu.step().cycles, cycles, "opcode {opcode:02X}");
+        assert_eq!(cpu.pc, PROGRAM_START + length, "opcode {opcode:02X}");
+        assert_eq!((cpu.a, cpu.x, cpu.y, cpu.s), (a, x, y, s));
+        assert_eq!(cpu.p.bits(), p);
+    }
+}
+
+#[test]
+fn cmos_rmw_reads_twice() {
+    // INC $10
+    let mut cpu = new_cpu(&[0xe6, 0x10], Cmos);
+    cpu.bus.memory[0x10] = 0x41;
+
+    assert_eq!(cpu.step().cycles, 5);
+    assert_eq!(cpu.bus.memory[0x10], 0x42);
+}
+
+#[test]
+fn cmos_interrupts_clear_decimal_flag() {
+    // SED; BRK
+    let mut cpu = new_cpu(&[0xf8, 0x00], Cmos);
+    cpu.step();
+    cpu.step();
+
+    assert!(!cpu.p.contains(Status::D));
+}
+
+#[test]
+fn rockwell_bit_instructions() {
+    let program = [
+        0x87, 0x10, // SMB0 $10
+        0x77, 0x10, // RMB7 $10
+        0x8f, 0x10, 0x02, // BBS0 $10, +2
+        0x00, 0x00, // BRK; BRK
+        0x7f, 0x10, 0xfd, // BBR7 $10, -3
+    ];
+    let mut cpu = new_cpu(&program, Rockwell);
+    cpu.bus.memory[0x10] = 0x80;
+
+    assert_eq!(cpu.step().cycles, 5);
+    assert_eq!(cpu.step().cycles, 5);
+    assert_eq!(cpu.bus.memory[0x10], 0x01);
+
+    assert_eq!(cpu.step().cycles, 6);
+    assert_eq!(cpu.pc, PROGRAM_START + 9);
+
+    assert_eq!(cpu.step().cycles, 6);
+    assert_eq!(cpu.pc, PROGRAM_START + 9);
+}
+
+#[test]
+fn mos6510_io_port() {
+    let program = [
+        0xa9, 0x07, // LDA #$07
+        0x85, 0x00, // STA $00
+        0xa9, 0xc5, // LDA #$C5
+        0x85, 0x01, // STA $01
+        0xa5, 0x00, // LDA $00
+        0xa5, 0x01, // LDA $01
+    ];
+    let mut cpu = new_cpu(&program, Mos6510);
+    for _ in 0..4 {
+        cpu.step();
+    }
+    assert_eq!(cpu.pins.port, 0xfd);
+
+    cpu.step();
+    assert_eq!(cpu.a, 0x07);
+
+    // The bus pulls input bit 4 low. Bits 6 and 7 aren't connected.
+    cpu.pins.port &= !0x10;
+    cpu.step();
+    assert_eq!(cpu.a, 0x2d);
+}
+
+#[test]
+fn mos6510_floating_bits_fade() {
+    let program = [
+        0xa9, 0xff, // LDA #$FF
+        0x85, 0x00, // STA $00
+        0xa9, 0xc0, // LDA #$C0
+        0x85, 0x01, // STA $01
+        0xa9, 0x3f, // LDA #$3F
+        0x85, 0x00, // STA $00
+        0xa5, 0x01, // LDA $01
+    ];
+    let mut cpu = new_cpu(&program, Mos6510);
+    for _ in 0..7 {
+        cpu.step();
+    }
+    assert_eq!(cpu.a, 0xc0);
+
+    cpu.cycles += 350_000;
+    cpu.pc = PROGRAM_START + 12;
+    cpu.step();
+    assert_eq!(cpu.a, 0x00);
+}
+
+#[test]
+fn mos6507_masks_addresses() {
+    // LDA $F000
+    let mut cpu = new_cpu(&[0xad, 0x00, 0xf0], Mos6507);
+    cpu.bus.memory[0x1000] = 0x42;
+    cpu.step();
+
+    assert_eq!(cpu.pins.address, 0x1000);
+    assert_eq!(cpu.a, 0x42);
+}
+
+#[test]
+fn mos6507_ignores_interrupts() {
+    // NOP; NOP; NOP
+    let mut cpu = new_cpu(&[0xea, 0xea, 0xea], Mos6507);
+    cpu.p.remove(Status::I);
+    cpu.pins.irq.assert(Source::new(0));
+    cpu.pins.nmi.assert(Source::new(0));
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.pc, PROGRAM_START + 2);
+
+    // The reset vector is read through the masked address bus.
+    cpu.bus.memory[0x1ffc] = 0x00;
+    cpu.bus.memory[0x1ffd] = 0xf4;
+    cpu.pins.rst = true;
+    cpu.step();
+    cpu.pins.rst = false;
+    cpu.step();
+    assert_eq!(cpu.pc, 0xf400);
+}
+
+#[test]
+fn unstable_magic_constants() {
+    // LDX #$FF; LDA #$00; ANE #$FF; LDA #$00; LXA #$FF
+    let program = [0xa2, 0xff, 0xa9, 0x00, 0x8b, 0xff, 0xa9, 0x00, 0xab, 0xff];
+
+    let mut cpu = new_cpu(&program, Mos6510);
+    cpu.step();
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.a, 0xef);
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.a, 0xee);
+
+    // The constants can be changed at runtime. The NES preset leaves ANE as
+    // a NOP.
+    let mut cpu = new_cpu(&program, Ricoh2A03);
+    assert_eq!(cpu.unstable, Unstable::NES);
+    cpu.unstable.lxa_magic = 0x00;
+    cpu.step();
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.a, 0x00);
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.a, 0x00);
+}
+
+#[test]
+fn sh_models() {
+    // SHX $12F0,Y
+    let program = [0x9e, 0xf0, 0x12];
+    for (sh, address, value) in [
+        (ShModel::PageCross, 0x1310, 0x13),
+        (ShModel::Always, 0x1410, 0x14),
+    ] {
+        let mut cpu = new_cpu(&program, Nmos);
+        cpu.unstable.sh = sh;
+        cpu.x = 0xff;
+        cpu.y = 0x20;
+        cpu.step();
+
+        assert_eq!(cpu.bus.memory[address], value, "{sh:?}");
+    }
+
+    // The NMOS default is the model the NMOS 6502 has always had.
+    let cpu = new_cpu(&program, Nmos);
+    assert_eq!(cpu.unstable, Unstable::NES);
+    assert_eq!(cpu.unstable.lxa_magic, 0xff);
+    assert_eq!(cpu.unstable.sh, ShModel::Always);
+}